@@ -1,40 +1,35 @@
-use std::process::Command;
-
-use opensubs::{Filters, Language, OrderBy, Response, SearchBy};
+use opensubs::{Filters, Language, OpenSubs, OrderBy, Response, SearchBy};
 
 #[tokio::main]
 async fn main() -> opensubs::Result {
-    // blocking search movie "pulp fiction", norwegian and swedish subs, order by date
-    let results = opensubs::search(SearchBy::MovieAndFilter(
-        "pulp fiction",
-        Filters::default()
-            .languages(&[Language::Norwegian, Language::Swedish])
-            .order_by(OrderBy::Uploaded)
-            .build(),
-    ))
-    .await?;
+    let client = OpenSubs::new()?;
+
+    // async search movie "pulp fiction", norwegian and swedish subs, order by date
+    let results = client
+        .search(SearchBy::MovieAndFilter(
+            "pulp fiction",
+            Filters::default()
+                .languages(&[Language::Norwegian, Language::Swedish])
+                .order_by(OrderBy::Uploaded)
+                .build(),
+        ))
+        .await?;
 
     if let Response::Subtitle(_, subtitles) = results {
-        // Example filtering by uploader and getting the download link
-        let link = subtitles
+        // Example filtering by uploader
+        let subtitle = subtitles
             .iter()
-            .find(|&sub| sub.uploader.as_ref().is_some_and(|s| s.contains("larza83")))
-            .map(|sub| sub.download_link.as_str());
-
-        println!("Link to download {link:#?}");
+            .find(|&sub| sub.uploader.as_ref().is_some_and(|s| s.contains("larza83")));
 
-        // Download using wget
-        let output = Command::new("wget")
-            .arg("-O")
-            .arg("subtitle.zip")
-            .arg(link.unwrap())
-            .output()
-            .expect("Failed to execute wget");
+        if let Some(subtitle) = subtitle {
+            println!("Downloading {}", subtitle.download_link);
 
-        if output.status.success() {
-            println!("Download successful!");
-        } else {
-            println!("Download failed.");
+            // Download the archive and extract its files (.nfo files are skipped).
+            // File names are stripped of their directories, so they stay in the current one
+            for file in client.download_files(subtitle).await? {
+                std::fs::write(&file.name, &file.bytes)?;
+                println!("Saved {}", file.name);
+            }
         }
     }
 
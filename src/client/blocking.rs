@@ -1,16 +1,381 @@
-use reqwest::{blocking::Client, header::USER_AGENT, redirect::Policy};
+use reqwest::{blocking::Client, redirect::Policy};
 
 use crate::{
-    client::SearchBy,
-    core::{Response, model::Subtitle},
+    client::{
+        CacheEntry, Config, Filter, HttpRequest, HttpResponse, MemoryTransport, OpenSubsBuilder,
+        SearchBy, pagination::Pages, redirect::Redirects,
+    },
+    core::{
+        Parsed, Response,
+        model::{Subtitle, SubtitleFile},
+    },
 };
 
+/// Blocking HTTP transport used by [`OpenSubs`] to send every request.
+///
+/// Implemented for [`reqwest::blocking::Client`] (the default) and for [`MemoryTransport`]. Implement it
+/// to run the client against a mock server or an alternative HTTP stack. Implementations must not
+/// follow redirections: they are handled by [`OpenSubs`].
+pub trait Transport: Send + Sync {
+    /// Sends an HTTP GET request and returns the raw response.
+    fn get(&self, request: &HttpRequest) -> crate::Result<HttpResponse>;
+}
+
+impl Transport for Client {
+    fn get(&self, request: &HttpRequest) -> crate::Result<HttpResponse> {
+        let mut builder = self.get(&request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.send()?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| Ok((name.to_string(), value.to_str()?.to_string())))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body: response.bytes()?.to_vec(),
+        })
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn get(&self, request: &HttpRequest) -> crate::Result<HttpResponse> {
+        Ok(self.respond(request))
+    }
+}
+
+/// Blocking opensubtitles.org client.
+///
+/// Holds a [`Transport`] (a [`reqwest::blocking::Client`] by default) and a [`Config`], so connection pools,
+/// cookies and settings are shared across every search. Cloning an `OpenSubs` is cheap and shares the same pool.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "blocking")]
+/// # {
+/// use opensubs::{blocking::OpenSubs, SearchBy};
+///
+/// fn some() -> opensubs::Result {
+///     let client = OpenSubs::builder().user_agent("my-app/1.0").build()?;
+///     let result = client.search(SearchBy::Movie("the godfather"))?;
+///     // handle result
+///     Ok(())
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenSubs<T = Client> {
+    transport: T,
+    config: Config,
+}
+
+impl OpenSubs {
+    /// Creates a client with the default [`Config`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new() -> crate::Result<Self> {
+        Self::builder().build()
+    }
+
+    /// Returns an [`OpenSubsBuilder`] to configure a new client.
+    pub fn builder() -> OpenSubsBuilder<Client> {
+        OpenSubsBuilder::default()
+    }
+}
+
+impl<T: Transport> OpenSubs<T> {
+    /// Returns the configuration of this client.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Performs a synchronous search using the provided [`SearchBy`] criteria, handling HTTP redirections manually.
+    ///
+    /// This method builds a search URL from the given `search_by` parameter against the configured
+    /// base URL, applies any necessary filters, and sends a synchronous HTTP GET request.
+    /// If the response is a redirection, it follows the `Location` header manually in a loop until
+    /// a non-redirection response is received. The final response is processed and returned as a [`Response`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// - The HTTP request fails.
+    /// - A redirection has a missing or invalid `Location` header, loops or exceeds the
+    ///   configured maximum.
+    /// - The site answers with a non-successful status, a captcha, maintenance or rate limiting page
+    ///   (see [`Error`](crate::Error)).
+    /// - Response processing fails.
+    pub fn search(&self, search_by: SearchBy) -> crate::Result<Response> {
+        let (_, parsed) = self.fetch(&search_by, true)?;
+        Ok(parsed.response)
+    }
+
+    /// Performs a search like [`search`](Self::search), also returning the fields that could not
+    /// be parsed.
+    ///
+    /// In [`ParseMode::Lenient`](crate::ParseMode::Lenient) (the default) such fields fall back to their defaults and are
+    /// reported as [`ParseWarning`](crate::ParseWarning)s; in [`ParseMode::Strict`](crate::ParseMode::Strict) the first one
+    /// fails with [`Error::Parse`](crate::Error::Parse) and the warnings are always empty.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`search`](Self::search).
+    pub fn search_with_warnings(&self, search_by: SearchBy<'_>) -> crate::Result<Parsed> {
+        let (_, parsed) = self.fetch(&search_by, true)?;
+        Ok(parsed)
+    }
+
+    /// Performs a search like [`search`](Self::search) bypassing the configured
+    /// [`Cache`](crate::Cache), which is refreshed with the fetched page.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`search`](Self::search).
+    pub fn search_fresh(&self, search_by: SearchBy) -> crate::Result<Response> {
+        let (_, parsed) = self.fetch(&search_by, false)?;
+        Ok(parsed.response)
+    }
+
+    /// Removes the cached page of the given search from the configured [`Cache`](crate::Cache), if any.
+    pub fn invalidate(&self, search_by: &SearchBy) {
+        if let Some(cache) = &self.config.cache {
+            cache.remove(&search_by.cache_key(&self.config.base_url));
+        }
+    }
+
+    /// Returns an iterator over every subtitle of a search, walking all the result pages.
+    ///
+    /// The first page is fetched with the given [`SearchBy`]; the following ones are requested
+    /// transparently through the `/offset=` URLs until [`Page::total`](crate::Page::total) is exhausted
+    /// or `max_items` subtitles have been yielded. If the search resolves to a movie or episode
+    /// listing the iterator is empty; search the `subtitles_link` of the desired entry instead.
+    ///
+    /// The iterator ends after yielding the first error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "blocking")]
+    /// # {
+    /// use opensubs::{blocking::OpenSubs, SearchBy};
+    ///
+    /// fn some(client: &OpenSubs) {
+    ///     for subtitle in client.subtitles(SearchBy::Imdb(68646), Some(100)) {
+    ///         // handle subtitle
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn subtitles<'a>(
+        &'a self,
+        search_by: SearchBy<'a>,
+        max_items: Option<usize>,
+    ) -> Subtitles<'a, T> {
+        Subtitles {
+            client: self,
+            search_by: Some(search_by),
+            pages: Pages::new(max_items),
+        }
+    }
+
+    /// Downloads the ZIP archive of the given [`Subtitle`].
+    ///
+    /// The redirections of `dl.opensubtitles.org` are followed manually and the raw archive bytes are returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails, the site answers with a non-successful status
+    /// or the redirections fail (missing or invalid `Location` header, loop or too many of them).
+    pub fn download(&self, subtitle: &Subtitle) -> crate::Result<Vec<u8>> {
+        let (_, response) = self.send(subtitle.download_link.clone(), None)?;
+        Ok(response.body)
+    }
+
+    /// Downloads the ZIP archive of the given [`Subtitle`] and extracts its files.
+    ///
+    /// The `.nfo` files bundled by the site are skipped. See [`SubtitleFile::extract`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the download fails or the archive cannot be extracted.
+    pub fn download_files(&self, subtitle: &Subtitle) -> crate::Result<Vec<SubtitleFile>> {
+        SubtitleFile::extract(&self.download(subtitle)?)
+    }
+
+    /// Performs a search and returns the final URL along with the [`Parsed`] response.
+    ///
+    /// The configured cache is read unless `cached` is `false`, and refreshed on success.
+    fn fetch(&self, search_by: &SearchBy, cached: bool) -> crate::Result<(String, Parsed)> {
+        let filter = search_by.filter();
+        let url = search_by.url(&self.config.base_url);
+        let key = search_by.cache_key(&self.config.base_url);
+        let cache = self.config.cache.as_deref();
+
+        if let Some(entry) = cache.filter(|_| cached).and_then(|cache| cache.get(&key)) {
+            let parsed = Response::parse(&entry.url, &entry.html, filter, self.config.parse_mode)?;
+            return Ok((entry.url, parsed));
+        }
+
+        let (url, response) = self.send(url, filter)?;
+        let html = response.text();
+        let parsed = Response::parse(&url, &html, filter, self.config.parse_mode)?;
+
+        if let Some(cache) = cache {
+            cache.put(&key, CacheEntry::new(url.clone(), html));
+        }
+        Ok((url, parsed))
+    }
+
+    /// Sends a GET request to `url`, retrying it according to the [`RetryPolicy`](crate::RetryPolicy).
+    ///
+    /// Returns the final URL along with the first non-redirection response.
+    fn send(&self, url: String, filter: Option<&Filter>) -> crate::Result<(String, HttpResponse)> {
+        let mut attempt = 1;
+        loop {
+            match self.send_once(url.clone(), filter) {
+                Err(e) => match self.config.retry_policy.delay(attempt, &e) {
+                    Some(delay) => std::thread::sleep(delay),
+                    None => return Err(e),
+                },
+                result => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Sends a GET request to `url`, following redirections manually.
+    ///
+    /// Returns the final URL along with the first non-redirection response. Fails when a
+    /// redirection has no `Location` header, leads back to a visited URL or exceeds
+    /// [`Config::max_redirects`].
+    fn send_once(
+        &self,
+        mut url: String,
+        filter: Option<&Filter>,
+    ) -> crate::Result<(String, HttpResponse)> {
+        let mut redirects = Redirects::new(self.config.max_redirects);
+        loop {
+            Subtitle::process_url(&mut url, filter);
+            redirects.visit(&url)?;
+
+            if let Some(rate_limiter) = &self.config.rate_limiter {
+                let delay = rate_limiter.acquire();
+                if !delay.is_zero() {
+                    std::thread::sleep(delay);
+                }
+            }
+
+            let response = self.transport.get(&self.config.request(&url))?;
+
+            if response.is_redirection() {
+                url = redirects.follow(&url, &response)?;
+            } else {
+                return Ok((url, response.error_for_status()?));
+            }
+        }
+    }
+}
+
+/// Iterator over every subtitle of a search, created by [`OpenSubs::subtitles`].
+#[derive(Debug)]
+pub struct Subtitles<'a, T = Client> {
+    client: &'a OpenSubs<T>,
+    search_by: Option<SearchBy<'a>>,
+    pages: Pages,
+}
+
+impl<T: Transport> Iterator for Subtitles<'_, T> {
+    type Item = crate::Result<Subtitle>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(subtitle) = self.pages.pop() {
+                return Some(Ok(subtitle));
+            }
+
+            let result = match self.search_by.take() {
+                Some(search_by) => self.client.fetch(&search_by, true),
+                None => self
+                    .client
+                    .fetch(&SearchBy::Url(&self.pages.next_url()?), true),
+            };
+
+            match result {
+                Ok((url, parsed)) => self.pages.push(&url, parsed.response),
+                Err(e) => {
+                    self.pages.finish();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl OpenSubsBuilder<Client> {
+    /// Builds the blocking [`OpenSubs`] client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built (e.g. invalid proxy or TLS backend).
+    pub fn build(self) -> crate::Result<OpenSubs> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = Client::builder().redirect(Policy::none());
+                if let Some(timeout) = self.config.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.config.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(proxy) = self.config.proxy.clone() {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(OpenSubs {
+            transport: client,
+            config: self.config,
+        })
+    }
+
+    /// Builds a blocking [`OpenSubs`] client sending its requests through `transport`.
+    ///
+    /// Timeouts, proxy and custom client settings are ignored; they are up to the transport.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "blocking")]
+    /// # {
+    /// use opensubs::{MemoryTransport, blocking::OpenSubs};
+    ///
+    /// let client = OpenSubs::builder().build_with(MemoryTransport::default());
+    /// # }
+    /// ```
+    pub fn build_with<T: Transport>(self, transport: T) -> OpenSubs<T> {
+        OpenSubs {
+            transport,
+            config: self.config,
+        }
+    }
+}
+
 /// Performs a synchronous search using the provided [`SearchBy`] criteria, handling HTTP redirections manually.
 ///
-/// This function builds a search URL from the given `search_by` parameter, applies any necessary filters,
-/// and sends a synchronous HTTP GET request using a [`reqwest::blocking::Client`] with redirection disabled.
-/// If the response is a redirection, it follows the `Location` header manually in a loop until a non-redirection
-/// response is received. The final response is processed and returned as a [`Response`].
+/// This function builds a one-shot [`OpenSubs`] client with the default [`Config`] and
+/// delegates to [`OpenSubs::search`]. Prefer a shared [`OpenSubs`] when performing many searches.
 ///
 /// # Arguments
 ///
@@ -25,7 +390,8 @@ use crate::{
 /// This function will return an error if:
 /// - The HTTP client cannot be built.
 /// - The HTTP request fails.
-/// - The `Location` header in a redirection cannot be parsed as a valid string.
+/// - A redirection has a missing or invalid `Location` header, loops or exceeds the maximum.
+/// - The site answers with a non-successful status, a captcha, maintenance or rate limiting page.
 /// - Response processing fails.
 ///
 /// # Example
@@ -47,35 +413,48 @@ use crate::{
 /// - This is a blocking synchronous version (available by cargo feature "blocking")
 #[allow(dead_code)]
 pub fn search(search_by: SearchBy) -> crate::Result<Response> {
-    let mut url: String = search_by.as_ref().into();
-    let filter = search_by.filter();
-    let client = Client::builder().redirect(Policy::none()).build()?;
-
-    loop {
-        Subtitle::process_url(&mut url, filter);
-
-        let response = client
-            .get(&url)
-            .header(USER_AGENT, "Mozilla/5.0 (Linux x86_64)")
-            .send()?;
-
-        if response.status().is_redirection() {
-            if let Some(location) = response.headers().get(reqwest::header::LOCATION) {
-                url = location.to_str()?.to_string();
-                if !url.contains("www.opensubtitles.org") {
-                    url = format!("https://www.opensubtitles.org{}", url);
-                }
-            }
-        } else {
-            return Response::create(&url, &response.text()?, filter);
-        }
-    }
+    OpenSubs::new()?.search(search_by)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::search;
-    use crate::{Response, SearchBy};
+    use std::time::{Duration, Instant};
+
+    use super::{OpenSubs, search};
+    use crate::{
+        Error, Filters, HttpResponse, Language, MemoryTransport, RateLimiter, Response,
+        RetryPolicy, SearchBy, Subtitle,
+    };
+
+    #[test]
+    fn test_search_with_transport() {
+        let search_by = SearchBy::ImdbAndFilter(
+            110912,
+            Filters::default().languages(&[Language::Norwegian]).build(),
+        );
+        let transport = MemoryTransport::default().with(
+            "https://www.opensubtitles.org/en/search/sublanguageid-nor/imdbid-110912/sort-5/asc-0",
+            HttpResponse::new(200, include_str!("../../tests/fixtures/subtitles.html")),
+        );
+        let client = OpenSubs::builder().build_with(transport);
+
+        let result = client.search(search_by).unwrap();
+        assert!(matches!(result, Response::Subtitle(_, ref subs) if subs.len() == 2));
+
+        let subtitles = client
+            .subtitles(
+                SearchBy::ImdbAndFilter(
+                    110912,
+                    Filters::default().languages(&[Language::Norwegian]).build(),
+                ),
+                None,
+            )
+            .collect::<Vec<_>>();
+        // The next page is not served by the transport
+        assert_eq!(subtitles.len(), 3);
+        assert!(subtitles[..2].iter().all(Result::is_ok));
+        assert!(matches!(subtitles[2], Err(Error::HttpStatus(404))));
+    }
 
     #[test]
     fn test_search_by_movie_and_filter() {
@@ -102,4 +481,87 @@ mod tests {
         assert!(result.is_ok());
         assert!(matches!(result.unwrap(), Response::Subtitle(_, _)));
     }
+
+    #[test]
+    fn test_retry_transient_status() {
+        let subtitle = Subtitle {
+            download_link: "https://dl.opensubtitles.org/en/download/sub/1".to_string(),
+            ..Default::default()
+        };
+        let transport = MemoryTransport::default().with_sequence(
+            &subtitle.download_link,
+            [HttpResponse::new(502, ""), HttpResponse::new(200, "PK")],
+        );
+        let client = OpenSubs::builder()
+            .retry_policy(RetryPolicy::default().backoff(Duration::ZERO, Duration::ZERO))
+            .build_with(transport.clone());
+
+        assert_eq!(client.download(&subtitle).unwrap(), b"PK");
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_retry_rate_limit_page() {
+        let url =
+            "https://www.opensubtitles.org/en/search/sublanguageid-nor/imdbid-110912/sort-5/asc-0";
+        let transport = MemoryTransport::default().with_sequence(
+            url,
+            [
+                HttpResponse::new(200, include_str!("../../tests/fixtures/captcha.html")),
+                HttpResponse::new(200, include_str!("../../tests/fixtures/subtitles.html")),
+            ],
+        );
+        let search_by = || {
+            SearchBy::ImdbAndFilter(
+                110912,
+                Filters::default().languages(&[Language::Norwegian]).build(),
+            )
+        };
+
+        let client = OpenSubs::builder().build_with(transport.clone());
+        assert!(matches!(client.search(search_by()), Err(Error::Captcha)));
+
+        transport.insert_sequence(
+            url,
+            [
+                HttpResponse::new(
+                    200,
+                    include_str!("../../tests/fixtures/too_many_requests.html"),
+                ),
+                HttpResponse::new(200, include_str!("../../tests/fixtures/subtitles.html")),
+            ],
+        );
+        let client = OpenSubs::builder()
+            .retry_policy(RetryPolicy::default().backoff(Duration::ZERO, Duration::ZERO))
+            .build_with(transport.clone());
+        let result = client.search(search_by()).unwrap();
+        assert!(matches!(result, Response::Subtitle(_, ref subs) if subs.len() == 2));
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_rate_limiter_shared_across_threads() {
+        let subtitle = Subtitle {
+            download_link: "https://dl.opensubtitles.org/en/download/sub/1".to_string(),
+            ..Default::default()
+        };
+        let transport =
+            MemoryTransport::default().with(&subtitle.download_link, HttpResponse::new(200, "PK"));
+        let client = OpenSubs::builder()
+            .rate_limiter(RateLimiter::per_second(20).burst(1))
+            .build_with(transport.clone());
+
+        let start = Instant::now();
+        std::thread::scope(|scope| {
+            for _ in 0..3 {
+                let client = client.clone();
+                let subtitle = &subtitle;
+                scope.spawn(move || client.download(subtitle).unwrap());
+            }
+        });
+
+        // One request is sent at once, the other two wait for a token every 50 ms
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(transport.requests().len(), 3);
+    }
 }
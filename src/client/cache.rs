@@ -0,0 +1,291 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A cached search result page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheEntry {
+    /// Final URL of the page, after following the redirections.
+    pub url: String,
+    /// Raw HTML of the page.
+    pub html: String,
+    /// When the page was fetched.
+    pub stored_at: SystemTime,
+}
+
+impl CacheEntry {
+    /// Creates an entry for a page fetched now.
+    pub fn new(url: impl Into<String>, html: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            html: html.into(),
+            stored_at: SystemTime::now(),
+        }
+    }
+
+    /// Returns `true` if the entry is older than `ttl`.
+    pub fn is_expired(&self, ttl: Duration) -> bool {
+        self.stored_at.elapsed().is_ok_and(|age| age > ttl)
+    }
+}
+
+/// Storage of search result pages, keyed by the URL built from the [`SearchBy`](crate::SearchBy).
+///
+/// The raw HTML is stored, so cached pages are parsed again with the current
+/// [`ParseMode`](crate::ParseMode). Only pages parsed successfully are stored and downloads
+/// are never cached. Caching is best effort: backends must not fail, a storage error is a miss.
+///
+/// Implemented by [`MemoryCache`] and [`FileCache`]; set one with
+/// [`OpenSubsBuilder::cache`](crate::OpenSubsBuilder::cache).
+pub trait Cache: Send + Sync + std::fmt::Debug {
+    /// Returns the entry stored under `key`, if any and not expired.
+    fn get(&self, key: &str) -> Option<CacheEntry>;
+
+    /// Stores `entry` under `key`, replacing any previous one.
+    fn put(&self, key: &str, entry: CacheEntry);
+
+    /// Removes the entry stored under `key`.
+    fn remove(&self, key: &str);
+
+    /// Removes every entry.
+    fn clear(&self);
+}
+
+/// In-memory [`Cache`] with a TTL, evicting the least recently used entries.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use opensubs::MemoryCache;
+///
+/// let cache = MemoryCache::new(100, Duration::from_secs(3600));
+/// ```
+#[derive(Debug)]
+pub struct MemoryCache {
+    /// Maximum number of entries.
+    capacity: usize,
+    /// Time to live of the entries.
+    ttl: Duration,
+    /// Entries and keys from least to most recently used.
+    entries: Mutex<(HashMap<String, CacheEntry>, VecDeque<String>)>,
+}
+
+impl MemoryCache {
+    /// Creates a cache holding up to `capacity` entries for `ttl` each.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ttl,
+            entries: Mutex::new((HashMap::new(), VecDeque::new())),
+        }
+    }
+}
+
+impl Cache for MemoryCache {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        let (entries, order) = &mut *self.entries.lock().expect("poisoned lock");
+        order.retain(|k| k != key);
+
+        match entries.get(key) {
+            Some(entry) if !entry.is_expired(self.ttl) => {
+                order.push_back(key.to_string());
+                Some(entry.clone())
+            }
+            _ => {
+                entries.remove(key);
+                None
+            }
+        }
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) {
+        let (entries, order) = &mut *self.entries.lock().expect("poisoned lock");
+        order.retain(|k| k != key);
+
+        while entries.len() >= self.capacity && !entries.contains_key(key) {
+            match order.pop_front() {
+                Some(oldest) => entries.remove(&oldest),
+                None => break,
+            };
+        }
+
+        entries.insert(key.to_string(), entry);
+        order.push_back(key.to_string());
+    }
+
+    fn remove(&self, key: &str) {
+        let (entries, order) = &mut *self.entries.lock().expect("poisoned lock");
+        entries.remove(key);
+        order.retain(|k| k != key);
+    }
+
+    fn clear(&self) {
+        let (entries, order) = &mut *self.entries.lock().expect("poisoned lock");
+        entries.clear();
+        order.clear();
+    }
+}
+
+/// File-system [`Cache`] with a TTL, persisting one file per entry under a directory.
+///
+/// Entries survive across runs. Expired entries are removed when read.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use opensubs::FileCache;
+///
+/// let cache = FileCache::new("/tmp/opensubs", Duration::from_secs(24 * 3600));
+/// ```
+#[derive(Debug)]
+pub struct FileCache {
+    /// Directory of the entry files.
+    dir: PathBuf,
+    /// Time to live of the entries.
+    ttl: Duration,
+}
+
+/// Extension of the entry files.
+const EXTENSION: &str = "cache";
+
+impl FileCache {
+    /// Creates a cache storing entries under `dir` (created on demand) for `ttl` each.
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+        }
+    }
+
+    /// Returns the path of the file of `key`, named after its FNV-1a hash.
+    fn path(&self, key: &str) -> PathBuf {
+        let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+        self.dir.join(format!("{hash:016x}.{EXTENSION}"))
+    }
+
+    /// Reads the entry file at `path`: the store time in seconds, the key and the final URL
+    /// on the first three lines, followed by the HTML.
+    fn read(path: &PathBuf, key: &str) -> Option<CacheEntry> {
+        let content = fs::read_to_string(path).ok()?;
+        let mut parts = content.splitn(4, '\n');
+
+        let stored_at = UNIX_EPOCH + Duration::from_secs(parts.next()?.parse().ok()?);
+        if parts.next()? != key {
+            return None;
+        }
+
+        Some(CacheEntry {
+            url: parts.next()?.to_string(),
+            html: parts.next()?.to_string(),
+            stored_at,
+        })
+    }
+}
+
+impl Cache for FileCache {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        let path = self.path(key);
+        let entry = Self::read(&path, key)?;
+
+        if entry.is_expired(self.ttl) {
+            let _ = fs::remove_file(path);
+            return None;
+        }
+        Some(entry)
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) {
+        let stored_at = entry
+            .stored_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let content = format!("{stored_at}\n{key}\n{}\n{}", entry.url, entry.html);
+
+        let _ = fs::create_dir_all(&self.dir).and_then(|_| fs::write(self.path(key), content));
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = fs::remove_file(self.path(key));
+    }
+
+    fn clear(&self) {
+        let Ok(files) = fs::read_dir(&self.dir) else {
+            return;
+        };
+
+        for path in files.filter_map(|file| file.ok().map(|file| file.path())) {
+            if path
+                .extension()
+                .is_some_and(|extension| extension == EXTENSION)
+            {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::{Cache, CacheEntry, FileCache, MemoryCache};
+
+    fn entry(url: &str) -> CacheEntry {
+        CacheEntry::new(url, "<html></html>")
+    }
+
+    #[test]
+    fn test_memory_cache_lru() {
+        let cache = MemoryCache::new(2, Duration::from_secs(60));
+        cache.put("a", entry("https://host/a"));
+        cache.put("b", entry("https://host/b"));
+
+        assert!(cache.get("a").is_some());
+        cache.put("c", entry("https://host/c"));
+
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a").unwrap().url, "https://host/a");
+        assert_eq!(cache.get("c").unwrap().url, "https://host/c");
+
+        cache.remove("a");
+        assert!(cache.get("a").is_none());
+    }
+
+    #[test]
+    fn test_memory_cache_ttl() {
+        let cache = MemoryCache::new(2, Duration::from_secs(60));
+        let mut old = entry("https://host/a");
+        old.stored_at = SystemTime::now() - Duration::from_secs(120);
+
+        cache.put("a", old);
+        assert!(cache.get("a").is_none());
+    }
+
+    #[test]
+    fn test_file_cache() {
+        let dir = std::env::temp_dir().join(format!("opensubs-cache-{}", std::process::id()));
+        let cache = FileCache::new(&dir, Duration::from_secs(60));
+
+        cache.put("https://host/search?a=1", entry("https://host/final"));
+        let cached = cache.get("https://host/search?a=1").unwrap();
+        assert_eq!(cached.url, "https://host/final");
+        assert_eq!(cached.html, "<html></html>");
+        assert!(cache.get("https://host/search?a=2").is_none());
+
+        let reopened = FileCache::new(&dir, Duration::ZERO);
+        assert!(reopened.get("https://host/search?a=1").is_none());
+        assert!(cache.get("https://host/search?a=1").is_none());
+
+        cache.put("key", entry("https://host/final"));
+        cache.clear();
+        assert!(cache.get("key").is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,230 @@
+use std::{sync::Arc, time::Duration};
+
+use super::{Cache, HttpRequest, RateLimiter, RetryPolicy};
+use crate::core::ParseMode;
+
+/// Default base URL of the opensubtitles.org site.
+pub(crate) const BASE_URL: &str = "https://www.opensubtitles.org";
+
+/// Default maximum number of redirections followed by a request.
+pub(crate) const MAX_REDIRECTS: usize = 10;
+
+/// Default `User-Agent` header sent with every request.
+pub(crate) const USER_AGENT: &str = "Mozilla/5.0 (Linux x86_64)";
+
+/// Returns the origin (scheme and host) of the given URL, falling back to [`BASE_URL`].
+pub(crate) fn origin(url: &str) -> String {
+    url::Url::parse(url)
+        .map(|url| url.origin().ascii_serialization())
+        .unwrap_or_else(|_| BASE_URL.to_string())
+}
+
+/// Configuration shared by the async and blocking `OpenSubs` clients.
+///
+/// This struct is usually created via [`OpenSubsBuilder`].
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Base URL of the site (scheme and host, without trailing slash).
+    pub(crate) base_url: String,
+    /// `User-Agent` header sent with every request.
+    pub(crate) user_agent: String,
+    /// Total timeout of every request.
+    pub(crate) timeout: Option<Duration>,
+    /// Timeout for the connect phase of every request.
+    pub(crate) connect_timeout: Option<Duration>,
+    /// Proxy used by every request.
+    pub(crate) proxy: Option<reqwest::Proxy>,
+    /// How unparseable fields of result pages are handled.
+    pub(crate) parse_mode: ParseMode,
+    /// Maximum number of redirections followed by a request.
+    pub(crate) max_redirects: usize,
+    /// Policy for retrying requests that failed with a transient error.
+    pub(crate) retry_policy: RetryPolicy,
+    /// Rate limiter every request passes through.
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    /// Cache of search result pages.
+    pub(crate) cache: Option<Arc<dyn Cache>>,
+}
+
+impl Default for Config {
+    /// Creates a `Config` pointing to opensubtitles.org without timeouts, proxy, retries, rate limit nor cache, parsing leniently.
+    fn default() -> Self {
+        Self {
+            base_url: BASE_URL.to_string(),
+            user_agent: USER_AGENT.to_string(),
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            parse_mode: ParseMode::default(),
+            max_redirects: MAX_REDIRECTS,
+            retry_policy: RetryPolicy::none(),
+            rate_limiter: None,
+            cache: None,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the configured base URL.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns the configured `User-Agent`.
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Returns the configured request timeout, if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Returns the configured connect timeout, if any.
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    /// Returns the configured [`ParseMode`].
+    pub fn parse_mode(&self) -> ParseMode {
+        self.parse_mode
+    }
+
+    /// Returns the configured maximum number of redirections.
+    pub fn max_redirects(&self) -> usize {
+        self.max_redirects
+    }
+
+    /// Returns the configured [`RetryPolicy`].
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Returns the configured [`RateLimiter`], if any.
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Returns the configured [`Cache`], if any.
+    pub fn cache(&self) -> Option<&dyn Cache> {
+        self.cache.as_deref()
+    }
+
+    /// Creates a GET request to `url` with the configured headers.
+    pub(crate) fn request(&self, url: &str) -> HttpRequest {
+        HttpRequest {
+            url: url.to_string(),
+            headers: vec![("User-Agent".to_string(), self.user_agent.clone())],
+        }
+    }
+}
+
+/// Builder for constructing an `OpenSubs` client with custom settings.
+///
+/// The same builder is used by the async client (`C` = [`reqwest::Client`]) and by the
+/// blocking one (`C` = `reqwest::blocking::Client`), so both share the same configuration surface.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "async")]
+/// # {
+/// use std::time::Duration;
+/// use opensubs::OpenSubs;
+///
+/// let client = OpenSubs::builder()
+///     .user_agent("my-app/1.0")
+///     .timeout(Duration::from_secs(10))
+///     .build()
+///     .expect("error building client");
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct OpenSubsBuilder<C> {
+    pub(crate) config: Config,
+    pub(crate) client: Option<C>,
+}
+
+impl<C> Default for OpenSubsBuilder<C> {
+    /// Creates an `OpenSubsBuilder` with the default [`Config`].
+    fn default() -> Self {
+        Self {
+            config: Config::default(),
+            client: None,
+        }
+    }
+}
+
+impl<C> OpenSubsBuilder<C> {
+    /// Sets the base URL of the site (e.g. a mirror or a local server).
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.config.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the `User-Agent` header.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = user_agent.into();
+        self
+    }
+
+    /// Sets the total timeout of every request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for the connect phase of every request.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the proxy used by every request.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.config.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets how unparseable fields of result pages are handled (lenient by default).
+    pub fn parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.config.parse_mode = parse_mode;
+        self
+    }
+
+    /// Sets the maximum number of redirections followed by a request (10 by default).
+    pub fn max_redirects(mut self, max_redirects: usize) -> Self {
+        self.config.max_redirects = max_redirects;
+        self
+    }
+
+    /// Sets the policy for retrying requests that failed with a transient error (no retries by default).
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.config.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the rate limiter every request passes through (no limit by default).
+    ///
+    /// The limiter is shared with the clones of the client and can be set on several clients.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.config.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Sets the cache of search result pages (no cache by default).
+    ///
+    /// See [`MemoryCache`](crate::MemoryCache) and [`FileCache`](crate::FileCache).
+    pub fn cache(mut self, cache: impl Cache + 'static) -> Self {
+        self.config.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Uses a custom HTTP client instead of building one.
+    ///
+    /// Timeouts and proxy are ignored when a custom client is supplied. The client should be
+    /// built with `reqwest::redirect::Policy::none()` so that redirections are handled by this crate.
+    pub fn client(mut self, client: C) -> Self {
+        self.client = Some(client);
+        self
+    }
+}
@@ -1,16 +1,370 @@
-use reqwest::{Client, header::USER_AGENT, redirect::Policy};
+use std::future::Future;
+
+use futures_util::{Stream, stream};
+use reqwest::{Client, redirect::Policy};
 
 use crate::{
-    client::SearchBy,
-    core::{Response, model::Subtitle},
+    client::{
+        CacheEntry, Config, Filter, HttpRequest, HttpResponse, MemoryTransport, OpenSubsBuilder,
+        SearchBy, pagination::Pages, redirect::Redirects,
+    },
+    core::{
+        Parsed, Response,
+        model::{Subtitle, SubtitleFile},
+    },
 };
 
+/// Asynchronous HTTP transport used by [`OpenSubs`] to send every request.
+///
+/// Implemented for [`reqwest::Client`] (the default) and for [`MemoryTransport`]. Implement it to
+/// run the client against a mock server or an alternative HTTP stack. Implementations must not
+/// follow redirections: they are handled by [`OpenSubs`].
+pub trait Transport: Send + Sync {
+    /// Sends an HTTP GET request and returns the raw response.
+    fn get(
+        &self,
+        request: &HttpRequest,
+    ) -> impl Future<Output = crate::Result<HttpResponse>> + Send;
+}
+
+impl Transport for Client {
+    async fn get(&self, request: &HttpRequest) -> crate::Result<HttpResponse> {
+        let mut builder = self.get(&request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| Ok((name.to_string(), value.to_str()?.to_string())))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body: response.bytes().await?.to_vec(),
+        })
+    }
+}
+
+impl Transport for MemoryTransport {
+    async fn get(&self, request: &HttpRequest) -> crate::Result<HttpResponse> {
+        Ok(self.respond(request))
+    }
+}
+
+/// Asynchronous opensubtitles.org client.
+///
+/// Holds a [`Transport`] (a [`reqwest::Client`] by default) and a [`Config`], so connection pools,
+/// cookies and settings are shared across every search. Cloning an `OpenSubs` is cheap and shares the same pool.
+///
+/// # Example
+///
+/// ```
+/// use opensubs::{OpenSubs, SearchBy};
+///
+/// async fn some() -> opensubs::Result {
+///     let client = OpenSubs::builder().user_agent("my-app/1.0").build()?;
+///     let result = client.search(SearchBy::Movie("the godfather")).await?;
+///     // handle result
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenSubs<T = Client> {
+    transport: T,
+    config: Config,
+}
+
+impl OpenSubs {
+    /// Creates a client with the default [`Config`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new() -> crate::Result<Self> {
+        Self::builder().build()
+    }
+
+    /// Returns an [`OpenSubsBuilder`] to configure a new client.
+    pub fn builder() -> OpenSubsBuilder<Client> {
+        OpenSubsBuilder::default()
+    }
+}
+
+impl<T: Transport> OpenSubs<T> {
+    /// Returns the configuration of this client.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Performs a search using the provided [`SearchBy`] criteria, handling manual HTTP redirections.
+    ///
+    /// This method constructs a search URL from the given `search_by` parameter against the
+    /// configured base URL, applies any necessary filters, and performs an HTTP GET request.
+    /// If the response is a redirection, it follows the `Location` header manually.
+    /// Otherwise, it processes the response and returns a [`Response`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The HTTP request fails.
+    /// - A redirection has a missing or invalid `Location` header, loops or exceeds the
+    ///   configured maximum.
+    /// - The site answers with a non-successful status, a captcha, maintenance or rate limiting page
+    ///   (see [`Error`](crate::Error)).
+    /// - Response processing fails.
+    pub async fn search(&self, search_by: SearchBy<'_>) -> crate::Result<Response> {
+        let (_, parsed) = self.fetch(&search_by, true).await?;
+        Ok(parsed.response)
+    }
+
+    /// Performs a search like [`search`](Self::search), also returning the fields that could not
+    /// be parsed.
+    ///
+    /// In [`ParseMode::Lenient`](crate::ParseMode::Lenient) (the default) such fields fall back to their defaults and are
+    /// reported as [`ParseWarning`](crate::ParseWarning)s; in [`ParseMode::Strict`](crate::ParseMode::Strict) the first one
+    /// fails with [`Error::Parse`](crate::Error::Parse) and the warnings are always empty.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`search`](Self::search).
+    pub async fn search_with_warnings(&self, search_by: SearchBy<'_>) -> crate::Result<Parsed> {
+        let (_, parsed) = self.fetch(&search_by, true).await?;
+        Ok(parsed)
+    }
+
+    /// Performs a search like [`search`](Self::search) bypassing the configured
+    /// [`Cache`](crate::Cache), which is refreshed with the fetched page.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`search`](Self::search).
+    pub async fn search_fresh(&self, search_by: SearchBy<'_>) -> crate::Result<Response> {
+        let (_, parsed) = self.fetch(&search_by, false).await?;
+        Ok(parsed.response)
+    }
+
+    /// Removes the cached page of the given search from the configured [`Cache`](crate::Cache), if any.
+    pub fn invalidate(&self, search_by: &SearchBy) {
+        if let Some(cache) = &self.config.cache {
+            cache.remove(&search_by.cache_key(&self.config.base_url));
+        }
+    }
+
+    /// Returns a stream over every subtitle of a search, walking all the result pages.
+    ///
+    /// The first page is fetched with the given [`SearchBy`]; the following ones are requested
+    /// transparently through the `/offset=` URLs until [`Page::total`](crate::Page::total) is exhausted
+    /// or `max_items` subtitles have been yielded. If the search resolves to a movie or episode
+    /// listing the stream is empty; search the `subtitles_link` of the desired entry instead.
+    ///
+    /// The stream ends after yielding the first error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::StreamExt;
+    /// use opensubs::{OpenSubs, SearchBy};
+    ///
+    /// async fn some(client: &OpenSubs) {
+    ///     let mut subtitles = std::pin::pin!(client.subtitles(SearchBy::Imdb(68646), Some(100)));
+    ///     while let Some(subtitle) = subtitles.next().await {
+    ///         // handle subtitle
+    ///     }
+    /// }
+    /// ```
+    pub fn subtitles<'a>(
+        &'a self,
+        search_by: SearchBy<'a>,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = crate::Result<Subtitle>> + 'a {
+        let state = (Pages::new(max_items), Some(search_by));
+
+        stream::unfold(state, move |(mut pages, mut search_by)| async move {
+            loop {
+                if let Some(subtitle) = pages.pop() {
+                    return Some((Ok(subtitle), (pages, search_by)));
+                }
+
+                let result = match search_by.take() {
+                    Some(search_by) => self.fetch(&search_by, true).await,
+                    None => self.fetch(&SearchBy::Url(&pages.next_url()?), true).await,
+                };
+
+                match result {
+                    Ok((url, parsed)) => pages.push(&url, parsed.response),
+                    Err(e) => {
+                        pages.finish();
+                        return Some((Err(e), (pages, None)));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Downloads the ZIP archive of the given [`Subtitle`].
+    ///
+    /// The redirections of `dl.opensubtitles.org` are followed manually and the raw archive bytes are returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP request fails, the site answers with a non-successful status
+    /// or the redirections fail (missing or invalid `Location` header, loop or too many of them).
+    pub async fn download(&self, subtitle: &Subtitle) -> crate::Result<Vec<u8>> {
+        let (_, response) = self.send(subtitle.download_link.clone(), None).await?;
+        Ok(response.body)
+    }
+
+    /// Downloads the ZIP archive of the given [`Subtitle`] and extracts its files.
+    ///
+    /// The `.nfo` files bundled by the site are skipped. See [`SubtitleFile::extract`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the download fails or the archive cannot be extracted.
+    pub async fn download_files(&self, subtitle: &Subtitle) -> crate::Result<Vec<SubtitleFile>> {
+        SubtitleFile::extract(&self.download(subtitle).await?)
+    }
+
+    /// Performs a search and returns the final URL along with the [`Parsed`] response.
+    ///
+    /// The configured cache is read unless `cached` is `false`, and refreshed on success.
+    async fn fetch(
+        &self,
+        search_by: &SearchBy<'_>,
+        cached: bool,
+    ) -> crate::Result<(String, Parsed)> {
+        let filter = search_by.filter();
+        let url = search_by.url(&self.config.base_url);
+        let key = search_by.cache_key(&self.config.base_url);
+        let cache = self.config.cache.as_deref();
+
+        if let Some(entry) = cache.filter(|_| cached).and_then(|cache| cache.get(&key)) {
+            let parsed = Response::parse(&entry.url, &entry.html, filter, self.config.parse_mode)?;
+            return Ok((entry.url, parsed));
+        }
+
+        let (url, response) = self.send(url, filter).await?;
+        let html = response.text();
+        let parsed = Response::parse(&url, &html, filter, self.config.parse_mode)?;
+
+        if let Some(cache) = cache {
+            cache.put(&key, CacheEntry::new(url.clone(), html));
+        }
+        Ok((url, parsed))
+    }
+
+    /// Sends a GET request to `url`, retrying it according to the [`RetryPolicy`](crate::RetryPolicy).
+    ///
+    /// Returns the final URL along with the first non-redirection response.
+    async fn send(
+        &self,
+        url: String,
+        filter: Option<&Filter<'_>>,
+    ) -> crate::Result<(String, HttpResponse)> {
+        let mut attempt = 1;
+        loop {
+            match self.send_once(url.clone(), filter).await {
+                Err(e) => match self.config.retry_policy.delay(attempt, &e) {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => return Err(e),
+                },
+                result => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Sends a GET request to `url`, following redirections manually.
+    ///
+    /// Returns the final URL along with the first non-redirection response. Fails when a
+    /// redirection has no `Location` header, leads back to a visited URL or exceeds
+    /// [`Config::max_redirects`].
+    async fn send_once(
+        &self,
+        mut url: String,
+        filter: Option<&Filter<'_>>,
+    ) -> crate::Result<(String, HttpResponse)> {
+        let mut redirects = Redirects::new(self.config.max_redirects);
+        loop {
+            Subtitle::process_url(&mut url, filter);
+            redirects.visit(&url)?;
+
+            if let Some(rate_limiter) = &self.config.rate_limiter {
+                let delay = rate_limiter.acquire();
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+
+            let response = self.transport.get(&self.config.request(&url)).await?;
+
+            if response.is_redirection() {
+                url = redirects.follow(&url, &response)?;
+            } else {
+                return Ok((url, response.error_for_status()?));
+            }
+        }
+    }
+}
+
+impl OpenSubsBuilder<Client> {
+    /// Builds the asynchronous [`OpenSubs`] client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built (e.g. invalid proxy or TLS backend).
+    pub fn build(self) -> crate::Result<OpenSubs> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = Client::builder().redirect(Policy::none());
+                if let Some(timeout) = self.config.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.config.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(proxy) = self.config.proxy.clone() {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(OpenSubs {
+            transport: client,
+            config: self.config,
+        })
+    }
+
+    /// Builds an asynchronous [`OpenSubs`] client sending its requests through `transport`.
+    ///
+    /// Timeouts, proxy and custom client settings are ignored; they are up to the transport.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use opensubs::{MemoryTransport, OpenSubs};
+    ///
+    /// let client = OpenSubs::builder().build_with(MemoryTransport::default());
+    /// ```
+    pub fn build_with<T: Transport>(self, transport: T) -> OpenSubs<T> {
+        OpenSubs {
+            transport,
+            config: self.config,
+        }
+    }
+}
+
 /// Performs a search using the provided [`SearchBy`] criteria, handling manual HTTP redirections.
 ///
-/// This function constructs a search URL from the given `search_by` parameter,
-/// applies any necessary filters, and performs an HTTP GET request using a [`reqwest::Client`]
-/// with redirection disabled. If the response is a redirection, it follows the `Location` header
-/// manually. Otherwise, it processes the response and returns a [`Response`].
+/// This function builds a one-shot [`OpenSubs`] client with the default [`Config`] and
+/// delegates to [`OpenSubs::search`]. Prefer a shared [`OpenSubs`] when performing many searches.
 ///
 /// # Arguments
 ///
@@ -26,7 +380,8 @@ use crate::{
 /// Returns an error if:
 /// - The HTTP client cannot be built.
 /// - The HTTP request fails.
-/// - The `Location` header in a redirection cannot be parsed.
+/// - A redirection has a missing or invalid `Location` header, loops or exceeds the maximum.
+/// - The site answers with a non-successful status, a captcha, maintenance or rate limiting page.
 /// - Response processing fails.
 ///
 /// # Example
@@ -45,40 +400,306 @@ use crate::{
 /// - Redirections are followed manually (not automatically by reqwest).
 /// - The loop continues following redirects until a non-redirection response is received.
 pub async fn search(search_by: SearchBy<'_>) -> crate::Result<Response> {
-    let mut url: String = search_by.as_ref().into();
-    let filter = search_by.filter();
-    let client = Client::builder().redirect(Policy::none()).build()?;
-
-    loop {
-        Subtitle::process_url(&mut url, filter);
-
-        let response = client
-            .get(&url)
-            .header(USER_AGENT, "Mozilla/5.0 (Linux x86_64)")
-            .send()
-            .await?;
-
-        if response.status().is_redirection() {
-            if let Some(location) = response.headers().get(reqwest::header::LOCATION) {
-                url = location.to_str()?.to_string();
-                if !url.contains("www.opensubtitles.org") {
-                    url = format!("https://www.opensubtitles.org{}", url);
-                }
-            }
-        } else {
-            return Response::create(&url, &response.text().await?, filter);
-        }
-    }
+    OpenSubs::new()?.search(search_by).await
 }
 
 #[cfg(test)]
 mod tests {
-    use super::search;
+    use std::time::Duration;
+
+    use futures_util::StreamExt;
+
+    use super::{OpenSubs, search};
     use crate::{
+        Error, Filters, HttpResponse, Language, MemoryCache, MemoryTransport, ParseMode,
+        RetryPolicy, Subtitle,
         client::{OrderBy, SearchBy},
         core::Response,
     };
 
+    const SUBTITLES: &str = include_str!("../../tests/fixtures/subtitles.html");
+    const SINGLE_RESULT: &str = include_str!("../../tests/fixtures/single_result.html");
+    const SUBTITLES_URL: &str =
+        "https://www.opensubtitles.org/en/search/sublanguageid-nor,swe/idmovie-8877/sort-5/asc-0";
+
+    fn search_by() -> SearchBy<'static> {
+        SearchBy::MovieAndFilter(
+            "pulp fiction",
+            Filters::default()
+                .languages(&[Language::Norwegian, Language::Swedish])
+                .build(),
+        )
+    }
+
+    fn transport() -> MemoryTransport {
+        MemoryTransport::default()
+            .with(
+                String::from(&search_by()),
+                HttpResponse::redirect("/en/search/sublanguageid-nor,swe/idmovie-8877"),
+            )
+            .with(SUBTITLES_URL, HttpResponse::new(200, SUBTITLES))
+            .with(
+                "https://www.opensubtitles.org/en/search/sublanguageid-nor,swe/idmovie-8877/offset=40/sort-5/asc-0",
+                HttpResponse::new(200, SINGLE_RESULT),
+            )
+    }
+
+    #[tokio::test]
+    async fn test_search_with_transport() {
+        let transport = transport();
+        let client = OpenSubs::builder()
+            .user_agent("opensubs-test")
+            .build_with(transport.clone());
+
+        let result = client.search(search_by()).await.unwrap();
+        assert!(matches!(result, Response::Subtitle(_, ref subs) if subs.len() == 2));
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].url, SUBTITLES_URL);
+        assert_eq!(requests[1].header("user-agent"), Some("opensubs-test"));
+    }
+
+    #[tokio::test]
+    async fn test_search_with_warnings() {
+        let transport = transport().with(
+            SUBTITLES_URL,
+            HttpResponse::new(200, SUBTITLES.replace(">87x<", ">n/a<")),
+        );
+
+        let lenient = OpenSubs::builder().build_with(transport.clone());
+        let parsed = lenient.search_with_warnings(search_by()).await.unwrap();
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].field, "downloads");
+
+        let strict = OpenSubs::builder()
+            .parse_mode(ParseMode::Strict)
+            .build_with(transport);
+        let result = strict.search(search_by()).await;
+        assert!(matches!(result, Err(Error::Parse { row: 3, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_loop_and_limit() {
+        let transport = MemoryTransport::default()
+            .with("https://host/a", HttpResponse::redirect("https://host/b"))
+            .with("https://host/b", HttpResponse::redirect("/a"));
+
+        let client = OpenSubs::builder().build_with(transport.clone());
+        let result = client.search(SearchBy::Url("https://host/a")).await;
+        assert!(matches!(result, Err(Error::RedirectLoop(url)) if url == "https://host/a"));
+
+        let client = OpenSubs::builder().max_redirects(0).build_with(transport);
+        let result = client.search(SearchBy::Url("https://host/a")).await;
+        assert!(matches!(result, Err(Error::TooManyRedirects(0))));
+    }
+
+    #[tokio::test]
+    async fn test_retry_rate_limit_page() {
+        let transport = transport().with_sequence(
+            SUBTITLES_URL,
+            [
+                HttpResponse::new(
+                    200,
+                    include_str!("../../tests/fixtures/too_many_requests.html"),
+                ),
+                HttpResponse::new(200, SUBTITLES),
+            ],
+        );
+        let client = OpenSubs::builder()
+            .retry_policy(RetryPolicy::default().backoff(Duration::ZERO, Duration::ZERO))
+            .build_with(transport.clone());
+
+        let result = client.search(search_by()).await.unwrap();
+        assert!(matches!(result, Response::Subtitle(_, ref subs) if subs.len() == 2));
+        assert_eq!(transport.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_status() {
+        let subtitle = Subtitle {
+            download_link: "https://dl.opensubtitles.org/en/download/sub/1".to_string(),
+            ..Default::default()
+        };
+        let transient = [
+            HttpResponse::new(503, ""),
+            HttpResponse::new(429, "").with_header("Retry-After", "0"),
+            HttpResponse::new(200, b"PK-bytes".to_vec()),
+        ];
+        let transport =
+            MemoryTransport::default().with_sequence(&subtitle.download_link, transient.clone());
+        let policy = RetryPolicy::default().backoff(Duration::ZERO, Duration::ZERO);
+
+        let client = OpenSubs::builder()
+            .retry_policy(policy.clone())
+            .build_with(transport.clone());
+        assert_eq!(client.download(&subtitle).await.unwrap(), b"PK-bytes");
+        assert_eq!(transport.requests().len(), 3);
+
+        // Non transient statuses are not retried
+        transport.insert(&subtitle.download_link, HttpResponse::new(404, ""));
+        assert!(matches!(
+            client.download(&subtitle).await,
+            Err(Error::HttpStatus(404))
+        ));
+        assert_eq!(transport.requests().len(), 4);
+
+        transport.insert_sequence(&subtitle.download_link, transient);
+        let client = OpenSubs::builder()
+            .retry_policy(policy.max_attempts(2))
+            .build_with(transport);
+        assert!(matches!(
+            client.download(&subtitle).await,
+            Err(Error::RateLimited { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_search_cache() {
+        let transport = transport();
+        let client = OpenSubs::builder()
+            .cache(MemoryCache::new(10, Duration::from_secs(60)))
+            .build_with(transport.clone());
+
+        let first = client.search(search_by()).await.unwrap();
+        let cached = client.search(search_by()).await.unwrap();
+        assert_eq!(first, cached);
+        assert_eq!(transport.requests().len(), 2);
+
+        client.search_fresh(search_by()).await.unwrap();
+        assert_eq!(transport.requests().len(), 4);
+
+        client.invalidate(&search_by());
+        client.search(search_by()).await.unwrap();
+        assert_eq!(transport.requests().len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_search_cache_pages_and_sort() {
+        let url = "https://www.opensubtitles.org/en/search/sublanguageid-nor/imdbid-110912";
+        let transport = MemoryTransport::default()
+            .with(
+                format!("{url}/sort-5/asc-0"),
+                HttpResponse::new(200, SUBTITLES),
+            )
+            .with(
+                format!("{url}/offset=40/sort-5/asc-0"),
+                HttpResponse::new(200, SINGLE_RESULT),
+            )
+            .with(
+                format!("{url}/sort-7/asc-0"),
+                HttpResponse::new(200, SINGLE_RESULT),
+            );
+        let client = OpenSubs::builder()
+            .cache(MemoryCache::new(10, Duration::from_secs(60)))
+            .build_with(transport.clone());
+        let search_by = |page, order_by| {
+            SearchBy::ImdbAndFilter(
+                110912,
+                Filters::default()
+                    .languages(&[Language::Norwegian])
+                    .page(page)
+                    .order_by(order_by)
+                    .build(),
+            )
+        };
+
+        let first = client
+            .search(search_by(1, OrderBy::Uploaded))
+            .await
+            .unwrap();
+        let second = client
+            .search(search_by(2, OrderBy::Uploaded))
+            .await
+            .unwrap();
+        assert_ne!(first, second);
+        assert_eq!(transport.requests().len(), 2);
+        assert_eq!(
+            transport.requests()[1].url,
+            format!("{url}/offset=40/sort-5/asc-0")
+        );
+
+        let sorted = client
+            .search(search_by(1, OrderBy::Downloads))
+            .await
+            .unwrap();
+        assert_ne!(first, sorted);
+        assert_eq!(transport.requests().len(), 3);
+        assert_eq!(transport.requests()[2].url, format!("{url}/sort-7/asc-0"));
+
+        let cached = client
+            .search(search_by(2, OrderBy::Uploaded))
+            .await
+            .unwrap();
+        assert_eq!(second, cached);
+        assert_eq!(transport.requests().len(), 3);
+
+        client.invalidate(&search_by(2, OrderBy::Uploaded));
+        client
+            .search(search_by(1, OrderBy::Uploaded))
+            .await
+            .unwrap();
+        assert_eq!(transport.requests().len(), 3);
+        client
+            .search(search_by(2, OrderBy::Uploaded))
+            .await
+            .unwrap();
+        assert_eq!(transport.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_subtitles_stream() {
+        let client = OpenSubs::builder().build_with(transport());
+
+        let ids = client
+            .subtitles(search_by(), None)
+            .map(|subtitle| subtitle.unwrap().id)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(ids, vec![9565071, 9100200, 9700001]);
+
+        let capped = client.subtitles(search_by(), Some(1)).count().await;
+        assert_eq!(capped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_download_with_transport() {
+        let subtitle = Subtitle {
+            download_link: "https://dl.opensubtitles.org/en/download/sub/1".to_string(),
+            ..Default::default()
+        };
+        let transport = MemoryTransport::default()
+            .with(
+                "https://dl.opensubtitles.org/en/download/sub/1",
+                HttpResponse::redirect("/en/download/file/1.zip"),
+            )
+            .with(
+                "https://dl.opensubtitles.org/en/download/file/1.zip",
+                HttpResponse::new(200, b"PK-bytes".to_vec()),
+            );
+        let client = OpenSubs::builder().build_with(transport);
+
+        assert_eq!(client.download(&subtitle).await.unwrap(), b"PK-bytes");
+    }
+
+    #[test]
+    fn test_builder_config() {
+        let client = OpenSubs::builder()
+            .base_url("http://localhost:8080/")
+            .user_agent("opensubs-test")
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        assert_eq!(client.config().base_url(), "http://localhost:8080");
+        assert_eq!(client.config().user_agent(), "opensubs-test");
+        assert_eq!(client.config().timeout(), Some(Duration::from_secs(5)));
+        assert_eq!(
+            SearchBy::Movie("the godfather").url(client.config().base_url()),
+            "http://localhost:8080/en/search2?MovieName=the+godfather&id=8&action=search"
+        );
+    }
+
     #[tokio::test]
     async fn test_fetch_url_async() {
         let result = search(SearchBy::MovieAndFilter(
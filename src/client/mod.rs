@@ -1,7 +1,20 @@
 #[cfg(feature = "blocking")]
 pub mod blocking;
 
+mod cache;
+mod config;
+#[cfg(feature = "async")]
 pub mod default;
+mod pagination;
 mod parameter;
+mod rate_limit;
+mod redirect;
+mod retry;
+mod transport;
 
+pub use cache::*;
+pub use config::*;
 pub use parameter::*;
+pub use rate_limit::*;
+pub use retry::*;
+pub use transport::*;
@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+
+use crate::core::{Response, model::Subtitle};
+
+/// State shared by the async subtitle stream and the blocking subtitle iterator.
+///
+/// Buffers the subtitles of the last fetched page and computes the `/offset=` URL
+/// of the next one until [`Page::total`](crate::Page::total) or the item cap is reached.
+#[derive(Debug, Default)]
+pub(crate) struct Pages {
+    /// Subtitles of the current page not yet yielded.
+    buffer: VecDeque<Subtitle>,
+    /// URL of the next page to fetch, if any.
+    next: Option<String>,
+    /// Number of items still allowed to be yielded (`None` means unlimited).
+    remaining: Option<usize>,
+}
+
+impl Pages {
+    /// Creates the pagination state with an optional cap of items.
+    pub(crate) fn new(max_items: Option<usize>) -> Self {
+        Self {
+            remaining: max_items,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the next buffered subtitle, honouring the item cap.
+    pub(crate) fn pop(&mut self) -> Option<Subtitle> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+
+        let subtitle = self.buffer.pop_front()?;
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+        }
+        Some(subtitle)
+    }
+
+    /// Takes the URL of the next page to fetch, if more items are needed.
+    pub(crate) fn next_url(&mut self) -> Option<String> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        self.next.take()
+    }
+
+    /// Stops the pagination (e.g. after an error).
+    pub(crate) fn finish(&mut self) {
+        self.buffer.clear();
+        self.next = None;
+    }
+
+    /// Buffers the subtitles of a fetched page located at `url` and prepares the next page URL.
+    ///
+    /// Movie and episode listings end the pagination.
+    pub(crate) fn push(&mut self, url: &str, response: Response) {
+        self.next = None;
+
+        if let Response::Subtitle(page, subtitles) = response {
+            if !subtitles.is_empty() && page.to > 0 && page.to < page.total {
+                self.next = Some(Subtitle::page_url(url, page.to));
+            }
+            self.buffer.extend(subtitles);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pages;
+    use crate::core::{Page, Response, model::Subtitle};
+
+    fn page(from: u32, to: u32, total: u32) -> Response {
+        let subtitles = (from..=to).map(|id| Subtitle {
+            id: id as u64,
+            ..Default::default()
+        });
+        Response::Subtitle(Page { from, to, total }, subtitles.collect())
+    }
+
+    #[test]
+    fn test_pages_walk() {
+        let mut pages = Pages::new(None);
+        pages.push(
+            "https://host/en/search/idmovie-1/sort-7/asc-0",
+            page(1, 40, 50),
+        );
+
+        assert_eq!((0..40).filter_map(|_| pages.pop()).count(), 40);
+        assert!(pages.pop().is_none());
+
+        let next = pages.next_url().unwrap();
+        assert_eq!(
+            next,
+            "https://host/en/search/idmovie-1/offset=40/sort-7/asc-0"
+        );
+
+        pages.push(&next, page(41, 50, 50));
+        assert_eq!(pages.pop().map(|subtitle| subtitle.id), Some(41));
+        assert!(pages.next_url().is_none());
+    }
+
+    #[test]
+    fn test_pages_max_items() {
+        let mut pages = Pages::new(Some(3));
+        pages.push("https://host/en/search/idmovie-1", page(1, 40, 500));
+
+        assert_eq!((0..10).filter_map(|_| pages.pop()).count(), 3);
+        assert!(pages.next_url().is_none());
+    }
+
+    #[test]
+    fn test_pages_movie_listing() {
+        let mut pages = Pages::new(None);
+        pages.push("https://host/en/search2", Response::Movie(Vec::new()));
+
+        assert!(pages.pop().is_none());
+        assert!(pages.next_url().is_none());
+    }
+}
@@ -1,6 +1,13 @@
+use super::config::BASE_URL;
+use crate::core::{
+    model::{SubFormat, Subtitle},
+    moviehash::MovieHash,
+};
+
 // Specifies the method and parameters for searching subtitles.
 ///
-/// This enum allows you to search by a direct URL, by movie name, or by movie name with additional filters.
+/// This enum allows you to search by a direct URL, by movie name, by movie name with additional filters
+/// by IMDb id (with optional filters) or by the [`moviehash`](crate::moviehash) of a local video file.
 #[derive(Debug)]
 pub enum SearchBy<'a> {
     /// Search using a direct URL.
@@ -9,6 +16,27 @@ pub enum SearchBy<'a> {
     Movie(&'a str),
     /// Search by movie name with additional filters.
     MovieAndFilter(&'a str, Filter<'a>),
+    /// Search subtitles by IMDb id (e.g. `68646` for "tt0068646").
+    Imdb(u32),
+    /// Search subtitles by IMDb id with additional filters (languages, page, order and subtitle filters).
+    ImdbAndFilter(u32, Filter<'a>),
+    /// Search by OpenSubtitles movie hash and file size (see [`moviehash`](crate::moviehash)).
+    Hash {
+        /// 64-bit OpenSubtitles hash of the video file.
+        hash: u64,
+        /// Size of the video file in bytes.
+        size: u64,
+    },
+}
+
+impl From<MovieHash> for SearchBy<'_> {
+    /// Converts a computed [`MovieHash`] into a `SearchBy::Hash`.
+    fn from(value: MovieHash) -> Self {
+        SearchBy::Hash {
+            hash: value.hash,
+            size: value.size,
+        }
+    }
 }
 
 impl<'a> AsRef<SearchBy<'a>> for SearchBy<'a> {
@@ -20,30 +48,123 @@ impl<'a> AsRef<SearchBy<'a>> for SearchBy<'a> {
 impl From<&SearchBy<'_>> for String {
     /// Converts a `SearchBy` variant into a URL string for querying OpenSubtitles.
     fn from(value: &SearchBy) -> Self {
-        let mut url = reqwest::Url::parse("https://www.opensubtitles.org/en/search2").unwrap();
-        match value {
+        value.url(BASE_URL)
+    }
+}
+
+impl<'a> SearchBy<'a> {
+    /// Returns a reference to the filter if present (`MovieAndFilter` or `ImdbAndFilter` variants), otherwise `None`.
+    pub(crate) fn filter(&self) -> Option<&Filter<'a>> {
+        match self {
+            SearchBy::MovieAndFilter(_, filter) | SearchBy::ImdbAndFilter(_, filter) => {
+                Some(filter)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the key the page of this search is cached under.
+    ///
+    /// Unlike [`url`](Self::url), it includes the page offset and sort order of the filter, which are
+    /// otherwise only added to the request once it resolves to a subtitle listing.
+    pub(crate) fn cache_key(&self, base_url: &str) -> String {
+        let mut key = self.url(base_url);
+        let filter = self.filter();
+        if Subtitle::is_subtitle(&key) {
+            Subtitle::process_url(&mut key, filter);
+        } else if let Some(filter) = filter {
+            key.push_str(&filter.offset().unwrap_or_default());
+            key.push_str(&filter.sort().unwrap_or_default());
+        }
+        key
+    }
+
+    /// Builds the search URL of this `SearchBy` against the given base URL.
+    pub(crate) fn url(&self, base_url: &str) -> String {
+        let search = format!("{base_url}/en/search2");
+        let mut url = match url::Url::parse(&search) {
+            Ok(url) => url,
+            Err(_) => return search,
+        };
+        match self {
             SearchBy::Url(url) => url.to_string(),
             SearchBy::Movie(movie) => {
                 url.query_pairs_mut().append_pair("MovieName", movie.trim());
-                format!(
-                    "https://www.opensubtitles.org/en/search2?MovieName={}&id=8&action=search",
-                    url.as_str()
-                )
+                format!("{}&id=8&action=search", url.as_str())
             }
             SearchBy::MovieAndFilter(movie, filter) => {
                 url.query_pairs_mut().append_pair("MovieName", movie.trim());
                 format!("{}&id=8&action=search{}", url.as_str(), filter.create())
             }
+            SearchBy::Imdb(id) => format!("{base_url}/en/search/sublanguageid-all/imdbid-{id}"),
+            SearchBy::ImdbAndFilter(id, filter) => {
+                let languages = filter.languages_to_str();
+                let languages = if languages.is_empty() {
+                    "all"
+                } else {
+                    &languages
+                };
+                format!(
+                    "{base_url}/en/search/sublanguageid-{languages}{}/imdbid-{id}",
+                    filter.segments()
+                )
+            }
+            SearchBy::Hash { hash, size } => format!(
+                "{base_url}/en/search/sublanguageid-all/moviebytesize-{size}/moviehash-{hash:016x}"
+            ),
         }
     }
 }
 
-impl<'a> SearchBy<'a> {
-    /// Returns a reference to the filter if present (`MovieAndFilter` variant), otherwise `None`.
-    pub(crate) fn filter(&self) -> Option<&Filter<'a>> {
-        match self {
-            SearchBy::MovieAndFilter(_, filter) => Some(filter),
-            _ => None,
+/// Release year condition of a search.
+///
+/// Exact, before and after years are sent to the site. Ranges are sent as "after the year
+/// before the start" and the movies released after the end are removed from the results.
+/// A `u32` converts into an exact year.
+///
+/// # Example
+/// ```
+/// use opensubs::{Filters, YearFilter};
+///
+/// let nineties = Filters::default().year(YearFilter::Between(1990, 1999)).build();
+/// let exact = Filters::default().year(2020).build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YearFilter {
+    /// Released in the given year.
+    Exact(u32),
+    /// Released before the given year (exclusive).
+    Before(u32),
+    /// Released after the given year (exclusive).
+    After(u32),
+    /// Released between both years (inclusive).
+    Between(u32, u32),
+}
+
+impl From<u32> for YearFilter {
+    fn from(year: u32) -> Self {
+        YearFilter::Exact(year)
+    }
+}
+
+impl YearFilter {
+    /// Returns `true` if a movie released in `year` matches this condition.
+    pub fn matches(&self, year: u32) -> bool {
+        match *self {
+            Self::Exact(exact) => year == exact,
+            Self::Before(before) => year < before,
+            Self::After(after) => year > after,
+            Self::Between(start, end) => (start.min(end)..=start.max(end)).contains(&year),
+        }
+    }
+
+    /// Returns the `MovieYearSign` (1 `=`, 2 `>`, 3 `<`) and `MovieYear` sent to the site.
+    fn query(&self) -> (u8, u32) {
+        match *self {
+            Self::Exact(year) => (1, year),
+            Self::After(year) => (2, year),
+            Self::Before(year) => (3, year),
+            Self::Between(start, end) => (2, start.min(end).saturating_sub(1)),
         }
     }
 }
@@ -68,18 +189,27 @@ impl Default for Filters<'_> {
     /// Creates a `Filters` builder with default parameters.
     fn default() -> Self {
         Self(Filter {
-            year: 0,
+            year: None,
             languages: &[],
             page: 1,
-            order_by: OrderBy::Uploaded,
+            sort: Sort::default(),
+            season: None,
+            episode: None,
+            hearing_impaired: None,
+            hd: false,
+            format: None,
+            cds: None,
+            fps: None,
+            trusted: false,
+            featured: false,
         })
     }
 }
 
 impl<'a> Filters<'a> {
-    /// Sets the year filter.
-    pub fn year(mut self, year: u32) -> Self {
-        self.0.year = year;
+    /// Sets the release year condition (an exact year if a `u32` is given).
+    pub fn year(mut self, year: impl Into<YearFilter>) -> Self {
+        self.0.year = Some(year.into());
         self
     }
 
@@ -95,9 +225,63 @@ impl<'a> Filters<'a> {
         self
     }
 
-    /// Sets the sorting order.
-    pub fn order_by(mut self, order_by: OrderBy) -> Self {
-        self.0.order_by = order_by;
+    /// Sets the sorting column and direction (descending if only an [`OrderBy`] is given).
+    pub fn order_by(mut self, sort: impl Into<Sort>) -> Self {
+        self.0.sort = sort.into();
+        self
+    }
+
+    /// Restricts the search to TV series, optionally to the given season.
+    pub fn season(mut self, season: u32) -> Self {
+        self.0.season = Some(season);
+        self
+    }
+
+    /// Restricts the search to TV series, optionally to the given episode.
+    pub fn episode(mut self, episode: u32) -> Self {
+        self.0.episode = Some(episode);
+        self
+    }
+
+    /// Keeps only subtitles for the hearing impaired (`true`) or excludes them (`false`).
+    pub fn hearing_impaired(mut self, hearing_impaired: bool) -> Self {
+        self.0.hearing_impaired = Some(hearing_impaired);
+        self
+    }
+
+    /// Keeps only subtitles for high definition releases when `hd` is `true`.
+    pub fn hd(mut self, hd: bool) -> Self {
+        self.0.hd = hd;
+        self
+    }
+
+    /// Keeps only subtitles of the given format.
+    pub fn format(mut self, format: SubFormat) -> Self {
+        self.0.format = Some(format);
+        self
+    }
+
+    /// Keeps only subtitles split in the given number of CDs.
+    pub fn cds(mut self, cds: u32) -> Self {
+        self.0.cds = Some(cds);
+        self
+    }
+
+    /// Keeps only subtitles timed for the given frames per second (e.g. `23.976`).
+    pub fn fps(mut self, fps: f32) -> Self {
+        self.0.fps = Some(fps);
+        self
+    }
+
+    /// Keeps only subtitles from trusted sources when `trusted` is `true`.
+    pub fn trusted(mut self, trusted: bool) -> Self {
+        self.0.trusted = trusted;
+        self
+    }
+
+    /// Keeps only featured subtitles when `featured` is `true`.
+    pub fn featured(mut self, featured: bool) -> Self {
+        self.0.featured = featured;
         self
     }
 
@@ -112,30 +296,109 @@ impl<'a> Filters<'a> {
 /// This struct is usually created via the [`Filters`] builder.
 #[derive(Debug)]
 pub struct Filter<'a> {
-    /// Year to filter by (0 means no filter).
-    year: u32,
+    /// Release year condition.
+    year: Option<YearFilter>,
     /// Languages to filter by.
     languages: &'a [Language],
     /// Page number for pagination (1-based).
     page: u32,
-    /// Sorting order.
-    order_by: OrderBy,
+    /// Sorting column and direction.
+    sort: Sort,
+    /// TV series season to filter by.
+    season: Option<u32>,
+    /// TV series episode to filter by.
+    episode: Option<u32>,
+    /// Only (`true`) or no (`false`) hearing impaired subtitles.
+    hearing_impaired: Option<bool>,
+    /// Only subtitles for high definition releases.
+    hd: bool,
+    /// Subtitle format to filter by.
+    format: Option<SubFormat>,
+    /// Number of CDs to filter by.
+    cds: Option<u32>,
+    /// Frames per second to filter by.
+    fps: Option<f32>,
+    /// Only subtitles from trusted sources.
+    trusted: bool,
+    /// Only featured subtitles.
+    featured: bool,
 }
 
 impl Filter<'_> {
     /// Creates a query string for the filter parameters.
     pub(crate) fn create(&self) -> String {
-        let year = if self.year != 0 {
-            self.year.to_string()
-        } else {
-            Default::default()
+        let (sign, year) = match self.year {
+            Some(year) => {
+                let (sign, year) = year.query();
+                (sign, year.to_string())
+            }
+            None => (1, Default::default()),
         };
 
-        format!(
-            "&SubLanguageID={}&MovieYearSign=1&MovieYear={}",
+        let mut query = format!(
+            "&SubLanguageID={}&MovieYearSign={sign}&MovieYear={year}",
             self.languages_to_str(),
-            year
-        )
+        );
+
+        if self.season.is_some() || self.episode.is_some() {
+            query.push_str("&SearchOnlyTVSeries=on");
+            if let Some(season) = self.season {
+                query.push_str(&format!("&Season={season}"));
+            }
+            if let Some(episode) = self.episode {
+                query.push_str(&format!("&Episode={episode}"));
+            }
+        }
+
+        for (name, _, value) in self.subtitle_filters() {
+            query.push_str(&format!("&{name}={value}"));
+        }
+
+        query
+    }
+
+    /// Creates the path segments of the subtitle filters (e.g. `/hearingimpaired-on`),
+    /// used by the `/en/search/` URLs.
+    pub(crate) fn segments(&self) -> String {
+        self.subtitle_filters()
+            .into_iter()
+            .map(|(_, segment, value)| format!("/{segment}-{value}"))
+            .collect()
+    }
+
+    /// Returns the set subtitle filters as `(query name, path segment, value)`.
+    fn subtitle_filters(&self) -> Vec<(&str, &str, String)> {
+        let on = || "on".to_string();
+        let mut filters = Vec::new();
+
+        if let Some(hearing_impaired) = self.hearing_impaired {
+            let value = if hearing_impaired { "on" } else { "off" };
+            filters.push(("SubHearingImpaired", "hearingimpaired", value.to_string()));
+        }
+        if self.hd {
+            filters.push(("SubHD", "hd", on()));
+        }
+        if let Some(format) = &self.format {
+            filters.push(("SubFormat", "subformat", format.as_str().to_string()));
+        }
+        if let Some(cds) = self.cds {
+            filters.push(("SubSumCD", "subsumcd", cds.to_string()));
+        }
+        if let Some(fps) = self.fps {
+            filters.push(("MovieFPS", "moviefps", format!("{fps:.3}")));
+        }
+        if self.trusted {
+            filters.push(("SubTrusted", "subtrusted", on()));
+        }
+        if self.featured {
+            filters.push(("SubFeatured", "subfeatured", on()));
+        }
+        filters
+    }
+
+    /// Returns the release year condition, if any.
+    pub(crate) fn year(&self) -> Option<YearFilter> {
+        self.year
     }
 
     /// Returns a comma-separated string of language codes.
@@ -155,14 +418,25 @@ impl Filter<'_> {
         (self.page > 1).then_some(format!("/offset={}", (self.page - 1) * 40))
     }
 
-    /// Returns the sort string for the current `OrderBy` option.
-    pub(crate) fn sort(&self) -> Option<&str> {
-        self.order_by.sort()
+    /// Returns the sort string for the current [`Sort`].
+    pub(crate) fn sort(&self) -> Option<String> {
+        Some(self.sort.to_path())
     }
 }
 
-// Specifies the sorting order for search results.
-#[derive(Debug, Clone)]
+/// Specifies the column results are sorted by.
+///
+/// Results are sorted in descending order unless [`ascending`](Self::ascending) is used.
+///
+/// # Example
+/// ```
+/// use opensubs::{Filters, OrderBy};
+///
+/// let filter = Filters::default().order_by(OrderBy::Downloads.ascending()).build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum OrderBy {
     /// Sort by upload date (default).
     Uploaded,
@@ -170,17 +444,91 @@ pub enum OrderBy {
     Downloads,
     /// Sort by rating.
     Rating,
+    /// Sort by movie name.
+    MovieName,
+    /// Sort by language.
+    Language,
+    /// Sort by number of CDs.
+    Cd,
+    /// Sort by uploader.
+    Uploader,
+    /// Sort by number of votes.
+    Votes,
+    /// Sort by number of comments.
+    Comments,
+    /// Sort by frames per second.
+    Fps,
 }
 
 impl OrderBy {
-    /// Returns the corresponding sort string for the order.
-    pub(crate) fn sort(&self) -> Option<&str> {
+    /// Returns the column number used by the site in the `/sort-N` segment.
+    fn column(&self) -> u8 {
         match self {
-            Self::Uploaded => Some("/sort-5/asc-0"),
-            Self::Downloads => Some("/sort-7/asc-0"),
-            Self::Rating => Some("/sort-6/asc-0"),
+            Self::MovieName => 1,
+            Self::Language => 2,
+            Self::Cd => 3,
+            Self::Fps => 4,
+            Self::Uploaded => 5,
+            Self::Rating => 6,
+            Self::Downloads => 7,
+            Self::Comments => 8,
+            Self::Votes => 9,
+            Self::Uploader => 10,
         }
     }
+
+    /// Sorts by this column in ascending order.
+    pub fn ascending(self) -> Sort {
+        Sort {
+            order_by: self,
+            ascending: true,
+        }
+    }
+
+    /// Sorts by this column in descending order (the default).
+    pub fn descending(self) -> Sort {
+        Sort {
+            order_by: self,
+            ascending: false,
+        }
+    }
+}
+
+/// Sorting of the search results: a column ([`OrderBy`]) and a direction.
+///
+/// Usually created with [`OrderBy::ascending`] or [`OrderBy::descending`]; an `OrderBy`
+/// converts into a descending `Sort`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sort {
+    /// Column to sort by.
+    pub order_by: OrderBy,
+    /// Whether the order is ascending.
+    pub ascending: bool,
+}
+
+impl Default for Sort {
+    /// Sorts by upload date in descending order.
+    fn default() -> Self {
+        OrderBy::Uploaded.into()
+    }
+}
+
+impl From<OrderBy> for Sort {
+    fn from(order_by: OrderBy) -> Self {
+        order_by.descending()
+    }
+}
+
+impl Sort {
+    /// Returns the corresponding sort string (e.g. `/sort-7/asc-1`).
+    pub(crate) fn to_path(&self) -> String {
+        format!(
+            "/sort-{}/asc-{}",
+            self.order_by.column(),
+            u8::from(self.ascending)
+        )
+    }
 }
 
 /// Represents all supported subtitle languages.
@@ -196,7 +544,9 @@ impl OrderBy {
 /// let code: &str = lang.into();
 /// assert_eq!(code, "eng");
 /// ```
-#[derive(Debug, Clone)]
+///
+/// With the `serde` feature, a `Language` is serialized as its code (e.g. `"eng"`).
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Language {
     Abkhazian,
     Afrikaans,
@@ -547,6 +897,149 @@ impl TryFrom<&str> for Language {
     }
 }
 
+impl Language {
+    /// Every supported language, in alphabetical order.
+    pub const ALL: [Language; 107] = [
+        Language::Abkhazian,
+        Language::Afrikaans,
+        Language::Albanian,
+        Language::Amharic,
+        Language::Arabic,
+        Language::Aragonese,
+        Language::Armenian,
+        Language::Assamese,
+        Language::Asturian,
+        Language::Azerbaijani,
+        Language::Basque,
+        Language::Belarusian,
+        Language::Bengali,
+        Language::Bosnian,
+        Language::Breton,
+        Language::Bulgarian,
+        Language::Burmese,
+        Language::Catalan,
+        Language::ChineseCantonese,
+        Language::ChineseSimplified,
+        Language::ChineseTraditional,
+        Language::ChineseBilingual,
+        Language::Croatian,
+        Language::Czech,
+        Language::Danish,
+        Language::Dari,
+        Language::Dutch,
+        Language::English,
+        Language::Esperanto,
+        Language::Estonian,
+        Language::Extremaduran,
+        Language::Finnish,
+        Language::French,
+        Language::Gaelic,
+        Language::Galician,
+        Language::Georgian,
+        Language::German,
+        Language::Greek,
+        Language::Hebrew,
+        Language::Hindi,
+        Language::Hungarian,
+        Language::Icelandic,
+        Language::Igbo,
+        Language::Indonesian,
+        Language::Interlingua,
+        Language::Irish,
+        Language::Italian,
+        Language::Japanese,
+        Language::Kannada,
+        Language::Kazakh,
+        Language::Khmer,
+        Language::Korean,
+        Language::Kurdish,
+        Language::Kyrgyz,
+        Language::Latvian,
+        Language::Lithuanian,
+        Language::Luxembourgish,
+        Language::Macedonian,
+        Language::Malay,
+        Language::Malayalam,
+        Language::Manipuri,
+        Language::Marathi,
+        Language::Mongolian,
+        Language::Montenegrin,
+        Language::Navajo,
+        Language::Nepali,
+        Language::NorthernSami,
+        Language::Norwegian,
+        Language::Occitan,
+        Language::Odia,
+        Language::Persian,
+        Language::Polish,
+        Language::Portuguese,
+        Language::PortugueseBr,
+        Language::PortugueseMz,
+        Language::Pushto,
+        Language::Romanian,
+        Language::Russian,
+        Language::Santali,
+        Language::Serbian,
+        Language::Sindhi,
+        Language::Sinhalese,
+        Language::Slovak,
+        Language::Slovenian,
+        Language::Somali,
+        Language::SorbianLanguages,
+        Language::SouthAzerbaijani,
+        Language::Spanish,
+        Language::SpanishEU,
+        Language::SpanishLA,
+        Language::Swahili,
+        Language::Swedish,
+        Language::Syriac,
+        Language::Tagalog,
+        Language::Tamil,
+        Language::Tatar,
+        Language::Telugu,
+        Language::Tetum,
+        Language::Thai,
+        Language::TokiPona,
+        Language::Turkish,
+        Language::Turkmen,
+        Language::Ukrainian,
+        Language::Urdu,
+        Language::Uzbek,
+        Language::Vietnamese,
+        Language::Welsch,
+    ];
+
+    /// Returns the language of the given OpenSubtitles code (case-insensitive), if any.
+    ///
+    /// # Example
+    /// ```
+    /// use opensubs::Language;
+    ///
+    /// assert_eq!(Language::from_code("spa"), Some(Language::Spanish));
+    /// ```
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|language| <&str>::from(language.clone()).eq_ignore_ascii_case(code))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Language {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.clone().into())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Language {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+        Self::from_code(&code)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown language code: {code}")))
+    }
+}
+
 /// Implements the `Display` trait for the `Language` enum.
 ///
 /// This implementation allows `Language` variants to be easily formatted into
@@ -559,3 +1052,158 @@ impl std::fmt::Display for Language {
         write!(f, "{:?}", self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Filters, Language, OrderBy, SearchBy, Sort, SubFormat, YearFilter};
+
+    #[test]
+    fn test_imdb_url() {
+        let url: String = (&SearchBy::Imdb(68646)).into();
+        assert_eq!(
+            url,
+            "https://www.opensubtitles.org/en/search/sublanguageid-all/imdbid-68646"
+        );
+    }
+
+    #[test]
+    fn test_imdb_and_filter_url() {
+        let search_by = SearchBy::ImdbAndFilter(
+            68646,
+            Filters::default()
+                .languages(&[Language::English, Language::Spanish])
+                .page(3)
+                .order_by(OrderBy::Downloads)
+                .build(),
+        );
+
+        let mut url: String = (&search_by).into();
+        crate::Subtitle::process_url(&mut url, search_by.filter());
+        assert_eq!(
+            url,
+            "https://www.opensubtitles.org/en/search/sublanguageid-eng,spa/imdbid-68646/offset=80/sort-7/asc-0"
+        );
+    }
+
+    #[test]
+    fn test_series_filter() {
+        let filter = Filters::default().season(2).episode(5).build();
+        assert_eq!(
+            filter.create(),
+            "&SubLanguageID=&MovieYearSign=1&MovieYear=&SearchOnlyTVSeries=on&Season=2&Episode=5"
+        );
+    }
+
+    #[test]
+    fn test_subtitle_filters() {
+        let filter = Filters::default()
+            .hearing_impaired(false)
+            .hd(true)
+            .format(SubFormat::Srt)
+            .cds(1)
+            .fps(23.976)
+            .trusted(true)
+            .build();
+        assert_eq!(
+            filter.create(),
+            "&SubLanguageID=&MovieYearSign=1&MovieYear=&SubHearingImpaired=off&SubHD=on&SubFormat=srt&SubSumCD=1&MovieFPS=23.976&SubTrusted=on"
+        );
+
+        let url: String = (&SearchBy::ImdbAndFilter(
+            68646,
+            Filters::default()
+                .languages(&[Language::English])
+                .hearing_impaired(true)
+                .featured(true)
+                .build(),
+        ))
+            .into();
+        assert_eq!(
+            url,
+            "https://www.opensubtitles.org/en/search/sublanguageid-eng/hearingimpaired-on/subfeatured-on/imdbid-68646"
+        );
+
+        let filter = Filters::default()
+            .hd(true)
+            .hd(false)
+            .trusted(false)
+            .featured(false)
+            .build();
+        assert_eq!(
+            filter.create(),
+            "&SubLanguageID=&MovieYearSign=1&MovieYear="
+        );
+    }
+
+    #[test]
+    fn test_hash_url() {
+        let url: String = (&SearchBy::Hash {
+            hash: 0x8e245d9679d31e12,
+            size: 12909756,
+        })
+            .into();
+        assert_eq!(
+            url,
+            "https://www.opensubtitles.org/en/search/sublanguageid-all/moviebytesize-12909756/moviehash-8e245d9679d31e12"
+        );
+    }
+
+    #[test]
+    fn test_language_from_code() {
+        assert_eq!(Language::from_code("ENG"), Some(Language::English));
+        assert_eq!(Language::from_code("amh"), Some(Language::Amharic));
+        assert_eq!(Language::from_code("xxx"), None);
+        assert!(
+            Language::ALL
+                .into_iter()
+                .all(|language| Language::from_code(language.clone().into()) == Some(language))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_language_and_order_by() {
+        assert_eq!(
+            serde_json::to_string(&[Language::Spanish, Language::PortugueseBr]).unwrap(),
+            r#"["spa","pob"]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Language>(r#""swe""#).unwrap(),
+            Language::Swedish
+        );
+        assert!(serde_json::from_str::<Language>(r#""Swedish""#).is_err());
+
+        assert_eq!(
+            serde_json::to_string(&OrderBy::Downloads).unwrap(),
+            r#""downloads""#
+        );
+    }
+
+    #[test]
+    fn test_sort_direction() {
+        assert_eq!(Sort::default().to_path(), "/sort-5/asc-0");
+        assert_eq!(OrderBy::Downloads.ascending().to_path(), "/sort-7/asc-1");
+        assert_eq!(Sort::from(OrderBy::Rating).to_path(), "/sort-6/asc-0");
+
+        let filter = Filters::default()
+            .order_by(OrderBy::MovieName.ascending())
+            .build();
+        assert_eq!(filter.sort().as_deref(), Some("/sort-1/asc-1"));
+    }
+
+    #[test]
+    fn test_year_filter() {
+        let query = |year: YearFilter| Filters::default().year(year).build().create();
+
+        assert!(query(2020.into()).ends_with("&MovieYearSign=1&MovieYear=2020"));
+        assert!(query(YearFilter::After(2010)).ends_with("&MovieYearSign=2&MovieYear=2010"));
+        assert!(query(YearFilter::Before(2000)).ends_with("&MovieYearSign=3&MovieYear=2000"));
+        assert!(
+            query(YearFilter::Between(1990, 1999)).ends_with("&MovieYearSign=2&MovieYear=1989")
+        );
+
+        assert!(YearFilter::Between(1999, 1990).matches(1990));
+        assert!(!YearFilter::Between(1990, 1999).matches(2000));
+        assert!(!YearFilter::After(2010).matches(2010));
+    }
+}
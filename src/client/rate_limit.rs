@@ -0,0 +1,131 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Token-bucket rate limiter throttling the requests sent to the site.
+///
+/// Every request of a search (including each redirection and retry) and of a download takes
+/// a token; when the bucket is empty the request waits until a token is refilled. The bucket
+/// is shared by the clones of a limiter, so a single limiter can be set on several clients
+/// (and the clones of a client) used concurrently from tasks or threads.
+///
+/// # Example
+/// ```
+/// use opensubs::RateLimiter;
+///
+/// // At most 30 requests per minute, allowing bursts of 5.
+/// let limiter = RateLimiter::per_minute(30).burst(5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+/// State of a [`RateLimiter`].
+#[derive(Debug)]
+struct Bucket {
+    /// Maximum number of stored tokens.
+    capacity: f64,
+    /// Available tokens; negative when requests are waiting for a refill.
+    tokens: f64,
+    /// Tokens refilled per second.
+    rate: f64,
+    /// Last time the tokens were refilled.
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `requests` per second, with a burst of the same size.
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    /// Creates a limiter allowing `requests` per minute, with a burst of the same size.
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    /// Creates a limiter allowing `requests` per `period`, with a burst of the same size.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        let requests = f64::from(requests.max(1));
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                capacity: requests,
+                tokens: requests,
+                rate: requests / period.as_secs_f64().max(f64::EPSILON),
+                refilled_at: Instant::now(),
+            })),
+        }
+    }
+
+    /// Sets the maximum number of requests sent at once after an idle period.
+    pub fn burst(self, burst: u32) -> Self {
+        {
+            let mut bucket = self.bucket.lock().expect("poisoned lock");
+            bucket.capacity = f64::from(burst.max(1));
+            bucket.tokens = bucket.capacity;
+        }
+        self
+    }
+
+    /// Takes a token and returns how long the caller must wait before sending its request.
+    pub(crate) fn acquire(&self) -> Duration {
+        self.acquire_at(Instant::now())
+    }
+
+    /// Takes a token at `now`, reserving a future one if the bucket is empty.
+    fn acquire_at(&self, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().expect("poisoned lock");
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * bucket.rate).min(bucket.capacity);
+        bucket.refilled_at = now;
+
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / bucket.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::RateLimiter;
+
+    #[test]
+    fn test_burst_then_wait() {
+        let limiter = RateLimiter::per_second(2).burst(3);
+        let now = Instant::now();
+
+        let waits = (0..5).map(|_| limiter.acquire_at(now)).collect::<Vec<_>>();
+        assert_eq!(
+            waits,
+            vec![
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::from_millis(500),
+                Duration::from_secs(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_refill_and_shared_clones() {
+        let limiter = RateLimiter::per_minute(60).burst(1);
+        let clone = limiter.clone();
+        let now = Instant::now();
+
+        assert_eq!(limiter.acquire_at(now), Duration::ZERO);
+        assert_eq!(clone.acquire_at(now), Duration::from_secs(1));
+        assert_eq!(
+            limiter.acquire_at(now + Duration::from_secs(3)),
+            Duration::ZERO
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+
+use url::Url;
+
+use super::HttpResponse;
+
+/// State of the manual redirect handling shared by the async and blocking clients.
+///
+/// Bounds the number of followed redirections and detects cycles over the visited URLs.
+#[derive(Debug)]
+pub(crate) struct Redirects {
+    /// URLs requested so far.
+    visited: HashSet<String>,
+    /// Maximum number of redirections to follow.
+    max: usize,
+}
+
+impl Redirects {
+    /// Creates the redirect state allowing up to `max` redirections.
+    pub(crate) fn new(max: usize) -> Self {
+        Self {
+            visited: HashSet::new(),
+            max,
+        }
+    }
+
+    /// Records a request to `url`, failing if it was already requested.
+    pub(crate) fn visit(&mut self, url: &str) -> crate::Result {
+        if !self.visited.insert(url.to_string()) {
+            return Err(crate::Error::RedirectLoop(url.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Resolves the target of the redirection `response` received from `url`.
+    ///
+    /// Relative `Location` headers are resolved against `url`.
+    pub(crate) fn follow(&self, url: &str, response: &HttpResponse) -> crate::Result<String> {
+        if self.visited.len() > self.max {
+            return Err(crate::Error::TooManyRedirects(self.max));
+        }
+
+        let location = response
+            .header("Location")
+            .ok_or(crate::Error::MissingLocation)?;
+        Ok(Url::parse(url)?.join(location)?.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Redirects;
+    use crate::{Error, HttpResponse};
+
+    #[test]
+    fn test_follow_relative_and_absolute() {
+        let mut redirects = Redirects::new(5);
+        let url = "https://host/en/search2?MovieName=x";
+        redirects.visit(url).unwrap();
+
+        let next = redirects
+            .follow(url, &HttpResponse::redirect("/en/search/idmovie-1"))
+            .unwrap();
+        assert_eq!(next, "https://host/en/search/idmovie-1");
+
+        let next = redirects
+            .follow(url, &HttpResponse::redirect("https://dl.host/file/1.zip"))
+            .unwrap();
+        assert_eq!(next, "https://dl.host/file/1.zip");
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut redirects = Redirects::new(1);
+        redirects.visit("https://host/a").unwrap();
+        assert!(matches!(
+            redirects.follow("https://host/a", &HttpResponse::new(302, "")),
+            Err(Error::MissingLocation)
+        ));
+
+        redirects.visit("https://host/b").unwrap();
+        assert!(matches!(
+            redirects.follow("https://host/b", &HttpResponse::redirect("/c")),
+            Err(Error::TooManyRedirects(1))
+        ));
+        assert!(matches!(
+            redirects.visit("https://host/a"),
+            Err(Error::RedirectLoop(url)) if url == "https://host/a"
+        ));
+    }
+}
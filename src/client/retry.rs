@@ -0,0 +1,198 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// Policy for retrying requests that failed with a transient error.
+///
+/// Applies to every request of a search or a download, in both the async and the blocking
+/// client. A failed attempt is retried when the error is a timeout, a connection error or an
+/// HTTP status listed in [`RetryPolicy::statuses`]. The delay grows exponentially from the
+/// base up to the cap; `Retry-After` of rate limited responses is honoured instead, up to the
+/// same cap.
+///
+/// Clients do not retry unless a policy is set with
+/// [`OpenSubsBuilder::retry_policy`](crate::OpenSubsBuilder::retry_policy).
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use opensubs::RetryPolicy;
+///
+/// let policy = RetryPolicy::default()
+///     .max_attempts(5)
+///     .backoff(Duration::from_secs(1), Duration::from_secs(60));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one.
+    max_attempts: u32,
+    /// Delay before the first retry.
+    base_delay: Duration,
+    /// Upper bound of the backoff delay.
+    max_delay: Duration,
+    /// Whether the backoff delay is randomized.
+    jitter: bool,
+    /// HTTP statuses considered transient.
+    statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    /// Creates a policy of 3 attempts with a backoff from 500 ms up to 30 s with jitter,
+    /// retrying on `429`, `500`, `502`, `503` and `504`.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            statuses: vec![429, 500, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy that never retries (the clients default).
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the maximum number of attempts, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry and the upper bound of the backoff.
+    pub fn backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Enables or disables the randomization of the backoff delay.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the HTTP statuses considered transient.
+    pub fn statuses(mut self, statuses: impl IntoIterator<Item = u16>) -> Self {
+        self.statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Returns the delay to wait before retrying after the `attempt`-th attempt (starting at 1)
+    /// failed with `error`, or `None` if it must not be retried.
+    pub(crate) fn delay(&self, attempt: u32, error: &crate::Error) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        match error {
+            crate::Error::RateLimited { retry_after } if self.statuses.contains(&429) => {
+                Some(match retry_after {
+                    Some(retry_after) => (*retry_after).min(self.max_delay),
+                    None => self.backoff_delay(attempt),
+                })
+            }
+            crate::Error::HttpStatus(status) if self.statuses.contains(status) => {
+                Some(self.backoff_delay(attempt))
+            }
+            crate::Error::ReqwestError(e) if e.is_timeout() || e.is_connect() => {
+                Some(self.backoff_delay(attempt))
+            }
+            _ => None,
+        }
+    }
+
+    /// Computes the exponential backoff delay of the `attempt`-th retry.
+    ///
+    /// With jitter the delay is a random value between its half and itself.
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt - 1);
+        let delay = self
+            .base_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+
+        if !self.jitter {
+            return delay;
+        }
+
+        let half = delay / 2;
+        let random = RandomState::new().build_hasher().finish();
+        half + half.mul_f64(random as f64 / u64::MAX as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+    use crate::Error;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::default()
+            .max_attempts(4)
+            .backoff(Duration::from_secs(1), Duration::from_secs(3))
+            .jitter(false)
+    }
+
+    #[test]
+    fn test_exponential_backoff() {
+        let error = Error::HttpStatus(503);
+        let delays = (1..=4)
+            .map(|attempt| policy().delay(attempt, &error))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_secs(1)),
+                Some(Duration::from_secs(2)),
+                Some(Duration::from_secs(3)),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        let policy = policy();
+        assert_eq!(policy.delay(1, &Error::HttpStatus(404)), None);
+        assert_eq!(policy.delay(1, &Error::Captcha), None);
+        assert_eq!(
+            policy.delay(
+                1,
+                &Error::RateLimited {
+                    retry_after: Some(Duration::from_secs(2))
+                }
+            ),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(RetryPolicy::none().delay(1, &Error::HttpStatus(503)), None);
+    }
+
+    #[test]
+    fn test_retry_after_capped() {
+        let error = Error::RateLimited {
+            retry_after: Some(Duration::from_secs(86400)),
+        };
+        assert_eq!(policy().delay(1, &error), Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn test_jitter_bounds() {
+        let policy = policy().jitter(true);
+        for _ in 0..20 {
+            let delay = policy.delay(2, &Error::HttpStatus(502)).unwrap();
+            assert!(delay >= Duration::from_secs(1) && delay <= Duration::from_secs(2));
+        }
+    }
+}
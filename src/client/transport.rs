@@ -0,0 +1,245 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::core::check_failure;
+
+/// An HTTP GET request sent through a transport.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpRequest {
+    /// Absolute URL of the request.
+    pub url: String,
+    /// Request headers as `(name, value)` pairs.
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    /// Returns the value of the first header named `name` (case-insensitive), if any.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// A raw HTTP response returned by a transport.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// Response headers as `(name, value)` pairs.
+    pub headers: Vec<(String, String)>,
+    /// Response body.
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Creates a response with the given status and body and no headers.
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Creates a `302 Found` redirection to `location`.
+    pub fn redirect(location: impl Into<String>) -> Self {
+        Self::new(302, Vec::new()).with_header("Location", location)
+    }
+
+    /// Adds a header to the response.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Returns the value of the first header named `name` (case-insensitive), if any.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Returns `true` if the status is a redirection (3xx).
+    pub fn is_redirection(&self) -> bool {
+        (300..400).contains(&self.status)
+    }
+
+    /// Returns the delay of the `Retry-After` header, if given in seconds.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.header("Retry-After")
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs)
+    }
+
+    /// Turns a failed response into the matching [`Error`](crate::Error).
+    ///
+    /// `429` is reported as [`Error::RateLimited`](crate::Error::RateLimited); any other status
+    /// outside `2xx` as a captcha or maintenance error when the body is such a page, otherwise
+    /// as [`Error::HttpStatus`](crate::Error::HttpStatus). HTML pages served with `2xx` are
+    /// checked too, as the site answers captchas and rate limiting with `200`; reporting them
+    /// here lets the [`RetryPolicy`](crate::RetryPolicy) retry them.
+    pub(crate) fn error_for_status(self) -> crate::Result<Self> {
+        match self.status {
+            200..=299 if self.is_html() => {
+                check_failure(&self.text())?;
+                Ok(self)
+            }
+            200..=299 => Ok(self),
+            429 => Err(crate::Error::RateLimited {
+                retry_after: self.retry_after(),
+            }),
+            status => {
+                check_failure(&self.text())?;
+                Err(crate::Error::HttpStatus(status))
+            }
+        }
+    }
+
+    /// Returns `true` if the body is an HTML page, by its `Content-Type` or its first byte
+    /// (archives and subtitle files are not).
+    fn is_html(&self) -> bool {
+        self.header("Content-Type")
+            .is_some_and(|value| value.to_ascii_lowercase().contains("html"))
+            || self.body.trim_ascii_start().starts_with(b"<")
+    }
+
+    /// Returns the body decoded as UTF-8, replacing invalid sequences.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Returns the value of the first header named `name` (case-insensitive), if any.
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// In-memory transport serving canned responses keyed by URL, intended for tests.
+///
+/// Unknown URLs are answered with a `404 Not Found`. A URL can also serve a sequence of
+/// responses (e.g. to test retries), the last one being repeated. Every request is recorded and can be
+/// inspected with [`MemoryTransport::requests`]. Clones share the same routes and records.
+///
+/// Implements both the async [`Transport`](crate::Transport) and the blocking
+/// `blocking::Transport`, depending on the enabled features.
+///
+/// # Example
+/// ```
+/// use opensubs::{HttpResponse, MemoryTransport};
+///
+/// let transport = MemoryTransport::default()
+///     .with("https://www.opensubtitles.org/en/search2", HttpResponse::new(200, "<html></html>"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTransport {
+    routes: Arc<Mutex<HashMap<String, VecDeque<HttpResponse>>>>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl MemoryTransport {
+    /// Serves `response` for every request to `url`.
+    pub fn with(self, url: impl Into<String>, response: HttpResponse) -> Self {
+        self.insert(url, response);
+        self
+    }
+
+    /// Serves `response` for every request to `url`, replacing any previous one.
+    pub fn insert(&self, url: impl Into<String>, response: HttpResponse) {
+        self.insert_sequence(url, [response]);
+    }
+
+    /// Serves `responses` in order to the requests to `url`, repeating the last one.
+    pub fn with_sequence(
+        self,
+        url: impl Into<String>,
+        responses: impl IntoIterator<Item = HttpResponse>,
+    ) -> Self {
+        self.insert_sequence(url, responses);
+        self
+    }
+
+    /// Serves `responses` in order to the requests to `url`, replacing any previous ones.
+    pub fn insert_sequence(
+        &self,
+        url: impl Into<String>,
+        responses: impl IntoIterator<Item = HttpResponse>,
+    ) {
+        self.routes
+            .lock()
+            .expect("poisoned lock")
+            .insert(url.into(), responses.into_iter().collect());
+    }
+
+    /// Returns the requests received so far, in order.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().expect("poisoned lock").clone()
+    }
+
+    /// Records `request` and returns its canned response.
+    pub(crate) fn respond(&self, request: &HttpRequest) -> HttpResponse {
+        self.requests
+            .lock()
+            .expect("poisoned lock")
+            .push(request.clone());
+
+        let mut routes = self.routes.lock().expect("poisoned lock");
+        let responses = routes.get_mut(&request.url);
+        match responses {
+            Some(responses) if responses.len() > 1 => responses.pop_front(),
+            Some(responses) => responses.front().cloned(),
+            None => None,
+        }
+        .unwrap_or_else(|| HttpResponse::new(404, Vec::new()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::HttpResponse;
+    use crate::Error;
+
+    #[test]
+    fn test_error_for_status() {
+        assert!(HttpResponse::new(200, "ok").error_for_status().is_ok());
+        assert!(matches!(
+            HttpResponse::new(
+                200,
+                include_str!("../../tests/fixtures/too_many_requests.html")
+            )
+            .error_for_status(),
+            Err(Error::RateLimited { retry_after: None })
+        ));
+        assert!(matches!(
+            HttpResponse::new(200, include_str!("../../tests/fixtures/captcha.html"))
+                .error_for_status(),
+            Err(Error::Captcha)
+        ));
+        assert!(matches!(
+            HttpResponse::new(404, "").error_for_status(),
+            Err(Error::HttpStatus(404))
+        ));
+        assert!(matches!(
+            HttpResponse::new(503, include_str!("../../tests/fixtures/maintenance.html"))
+                .error_for_status(),
+            Err(Error::Maintenance)
+        ));
+    }
+
+    #[test]
+    fn test_rate_limited_retry_after() {
+        let response = HttpResponse::new(429, "").with_header("retry-after", "120");
+        assert!(matches!(
+            response.error_for_status(),
+            Err(Error::RateLimited { retry_after: Some(delay) }) if delay == Duration::from_secs(120)
+        ));
+
+        let response =
+            HttpResponse::new(429, "").with_header("Retry-After", "Wed, 21 Oct 2026 07:28:00 GMT");
+        assert_eq!(response.retry_after(), None);
+    }
+}
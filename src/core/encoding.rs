@@ -0,0 +1,298 @@
+//! Character encoding detection of downloaded subtitle files.
+//!
+//! Subtitles on opensubtitles.org are uploaded in whatever encoding the author used: legacy
+//! Windows and ISO-8859 code pages, Big5, GBK, Shift_JIS or UTF-16. [`decode`] detects the
+//! encoding of a file and transcodes it to UTF-8.
+//!
+//! Byte order marks and valid UTF-8 are trusted as is; UTF-16 without BOM is recognized by its
+//! zero bytes; any other file goes through the statistical detector of
+//! [`chardetng`](https://docs.rs/chardetng), biased by the language of the subtitle.
+//!
+//! # Example
+//! ```
+//! use opensubs::{Language, encoding::{self, Confidence}};
+//!
+//! // "Привет" in windows-1251
+//! let bytes = [0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2, 0x2C, 0x20, 0xEC, 0xE8, 0xF0];
+//! let decoded = encoding::decode(&bytes, Some(Language::Russian));
+//! assert_eq!(decoded.text, "Привет, мир");
+//! assert_eq!(decoded.encoding, "windows-1251");
+//! ```
+
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8, UTF_16BE, UTF_16LE};
+
+use crate::Language;
+
+/// Number of leading bytes inspected to recognize UTF-16 without BOM.
+const UTF_16_SAMPLE: usize = 4096;
+
+/// How much the detected encoding can be trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    /// The text decoded with replacement characters: the guess is likely wrong.
+    Low,
+    /// The statistical detector is confident, or the file is UTF-16 without BOM.
+    High,
+    /// The file starts with a byte order mark or is valid UTF-8.
+    Certain,
+}
+
+/// A subtitle file transcoded to UTF-8.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    /// Content of the file, without byte order mark.
+    pub text: String,
+    /// WHATWG name of the detected encoding (e.g. `"windows-1251"`, `"UTF-16LE"`).
+    pub encoding: &'static str,
+    /// How much the detected encoding can be trusted.
+    pub confidence: Confidence,
+    /// Whether malformed sequences were replaced by `U+FFFD`.
+    pub had_errors: bool,
+}
+
+/// Detects the encoding of `bytes` and transcodes them to UTF-8.
+///
+/// `language` biases the detection towards the encodings used for that language (e.g.
+/// windows-1251 for Russian, GBK for simplified Chinese). It is only a hint: a file whose
+/// content clearly belongs to another encoding is still detected as such.
+pub fn decode(bytes: &[u8], language: Option<Language>) -> Decoded {
+    if let Some((encoding, bom)) = Encoding::for_bom(bytes) {
+        return transcode(&bytes[bom..], encoding, Confidence::Certain);
+    }
+    if let Some(encoding) = utf_16_without_bom(bytes) {
+        return transcode(bytes, encoding, Confidence::High);
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return transcode(bytes, UTF_8, Confidence::Certain);
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let tld = language.and_then(tld);
+    let (encoding, high) = detector.guess_assess(tld.map(str::as_bytes), false);
+
+    transcode(
+        bytes,
+        encoding,
+        if high {
+            Confidence::High
+        } else {
+            Confidence::Low
+        },
+    )
+}
+
+/// Decodes `bytes` without BOM handling, lowering the confidence if malformed sequences were
+/// found.
+fn transcode(bytes: &[u8], encoding: &'static Encoding, confidence: Confidence) -> Decoded {
+    let (text, had_errors) = encoding.decode_without_bom_handling(bytes);
+
+    Decoded {
+        text: text.into_owned(),
+        encoding: encoding.name(),
+        confidence: if had_errors {
+            Confidence::Low
+        } else {
+            confidence
+        },
+        had_errors,
+    }
+}
+
+/// Recognizes UTF-16 without BOM by the zero high bytes of its ASCII characters (digits,
+/// timestamps and punctuation are always present in subtitles).
+fn utf_16_without_bom(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(UTF_16_SAMPLE) & !1];
+    if sample.is_empty() {
+        return None;
+    }
+
+    let pairs = sample.len() / 2;
+    let zeros = |offset: usize| {
+        sample
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .filter(|byte| **byte == 0)
+            .count()
+    };
+    let (even, odd) = (zeros(0), zeros(1));
+
+    if odd * 10 >= pairs * 4 && even * 20 < pairs {
+        Some(UTF_16LE)
+    } else if even * 10 >= pairs * 4 && odd * 20 < pairs {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Returns the top-level domain of the country whose legacy encodings match `language`, used as
+/// hint by the detector.
+fn tld(language: Language) -> Option<&'static str> {
+    Some(match language {
+        Language::Arabic => "sa",
+        Language::Armenian => "am",
+        Language::Azerbaijani | Language::SouthAzerbaijani => "az",
+        Language::Belarusian => "by",
+        Language::Bosnian => "ba",
+        Language::Bulgarian => "bg",
+        Language::ChineseSimplified | Language::ChineseBilingual => "cn",
+        Language::ChineseTraditional => "tw",
+        Language::ChineseCantonese => "hk",
+        Language::Croatian => "hr",
+        Language::Czech => "cz",
+        Language::Danish => "dk",
+        Language::Dutch => "nl",
+        Language::Estonian => "ee",
+        Language::Finnish => "fi",
+        Language::French => "fr",
+        Language::Georgian => "ge",
+        Language::German => "de",
+        Language::Greek => "gr",
+        Language::Hebrew => "il",
+        Language::Hungarian => "hu",
+        Language::Icelandic => "is",
+        Language::Indonesian => "id",
+        Language::Italian => "it",
+        Language::Japanese => "jp",
+        Language::Kazakh => "kz",
+        Language::Korean => "kr",
+        Language::Latvian => "lv",
+        Language::Lithuanian => "lt",
+        Language::Macedonian => "mk",
+        Language::Montenegrin => "me",
+        Language::Norwegian => "no",
+        Language::Dari | Language::Persian => "ir",
+        Language::Polish => "pl",
+        Language::Portuguese | Language::PortugueseMz => "pt",
+        Language::PortugueseBr => "br",
+        Language::Romanian => "ro",
+        Language::Russian => "ru",
+        Language::Serbian => "rs",
+        Language::Slovak => "sk",
+        Language::Slovenian => "si",
+        Language::Spanish | Language::SpanishEU | Language::SpanishLA => "es",
+        Language::Swedish => "se",
+        Language::Thai => "th",
+        Language::Turkish => "tr",
+        Language::Ukrainian => "ua",
+        Language::Urdu => "pk",
+        Language::Vietnamese => "vn",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{BIG5, GBK, SHIFT_JIS, WINDOWS_1250, WINDOWS_1251, WINDOWS_1252};
+
+    use super::{Confidence, decode};
+    use crate::{Language, Subtitle, SubtitleFile};
+
+    const SRT: &str = "1\r\n00:00:01,000 --> 00:00:02,000\r\n";
+
+    fn encode(encoding: &'static encoding_rs::Encoding, text: &str) -> Vec<u8> {
+        let text = format!("{SRT}{text}\r\n");
+        let (bytes, _, had_errors) = encoding.encode(&text);
+        assert!(!had_errors);
+        bytes.into_owned()
+    }
+
+    #[test]
+    fn test_decode_unicode() {
+        let decoded = decode("\u{feff}1\nÁrvíztűrő".as_bytes(), None);
+        assert_eq!(decoded.text, "1\nÁrvíztűrő");
+        assert_eq!(decoded.encoding, "UTF-8");
+        assert_eq!(decoded.confidence, Confidence::Certain);
+
+        let utf_16 = |bom: &[u8], le: bool| {
+            let mut bytes = bom.to_vec();
+            for unit in format!("{SRT}¿Qué?").encode_utf16() {
+                bytes.extend(if le {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                });
+            }
+            decode(&bytes, None)
+        };
+
+        let decoded = utf_16(&[0xFF, 0xFE], true);
+        assert_eq!(decoded.text, format!("{SRT}¿Qué?"));
+        assert_eq!(
+            (decoded.encoding, decoded.confidence),
+            ("UTF-16LE", Confidence::Certain)
+        );
+
+        let decoded = utf_16(&[], true);
+        assert_eq!(decoded.text, format!("{SRT}¿Qué?"));
+        assert_eq!(
+            (decoded.encoding, decoded.confidence),
+            ("UTF-16LE", Confidence::High)
+        );
+
+        let decoded = utf_16(&[], false);
+        assert_eq!(decoded.text, format!("{SRT}¿Qué?"));
+        assert_eq!(decoded.encoding, "UTF-16BE");
+    }
+
+    #[test]
+    fn test_decode_legacy() {
+        let cases = [
+            (
+                WINDOWS_1251,
+                Language::Russian,
+                "Я не знаю, что с ним случилось. Он ушёл вчера вечером и больше не вернулся.",
+            ),
+            (
+                WINDOWS_1250,
+                Language::Polish,
+                "Nie wiem, co się z nim stało. Wyszedł wczoraj wieczorem i już nie wrócił. Zażółć gęślą jaźń, świat się kończy.",
+            ),
+            (
+                WINDOWS_1252,
+                Language::Spanish,
+                "No sé qué le pasó. Salió ayer por la noche y no volvió. ¿Dónde está el niño?",
+            ),
+            (
+                GBK,
+                Language::ChineseSimplified,
+                "我不知道他发生了什么事。他昨天晚上出去了，再也没有回来。",
+            ),
+            (
+                BIG5,
+                Language::ChineseTraditional,
+                "我不知道他發生了什麼事。他昨天晚上出去了，再也沒有回來。",
+            ),
+            (
+                SHIFT_JIS,
+                Language::Japanese,
+                "彼に何が起きたのか分かりません。昨日の夜に出かけて、それきり戻りませんでした。",
+            ),
+        ];
+
+        for (encoding, language, text) in cases {
+            let decoded = decode(&encode(encoding, text), Some(language.clone()));
+            assert_eq!(decoded.encoding, encoding.name(), "{language:?}");
+            assert_eq!(decoded.text, format!("{SRT}{text}\r\n"));
+            assert!(!decoded.had_errors);
+        }
+    }
+
+    #[test]
+    fn test_decode_subtitle_language_hint() {
+        let subtitle = Subtitle {
+            language: "Russian".to_string(),
+            ..Default::default()
+        };
+        let file = SubtitleFile {
+            name: "movie.srt".to_string(),
+            bytes: encode(WINDOWS_1251, "Привет"),
+        };
+        let decoded = file.decode(&subtitle);
+        assert_eq!(decoded.encoding, "windows-1251");
+        assert_eq!(decoded.text, format!("{SRT}Привет\r\n"));
+    }
+}
@@ -0,0 +1,199 @@
+//! SubStation Alpha (`.ssa`) and Advanced SubStation Alpha (`.ass`) reader and writer.
+
+use super::{Cue, Metadata, SubFormat, SubtitleDocument, clock, parse_timestamp};
+
+/// Fields of an event line when the `[Events]` section has no `Format:` line.
+const EVENT_FIELDS: [&str; 10] = [
+    "layer", "start", "end", "style", "name", "marginl", "marginr", "marginv", "effect", "text",
+];
+
+/// Style written when the document has no ASS styles.
+const ASS_STYLE: &str = "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, \
+OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, \
+BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1";
+
+/// Style written when the document has no SSA styles.
+const SSA_STYLE: &str = "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, \
+TertiaryColour, BackColour, Bold, Italic, BorderStyle, Outline, Shadow, Alignment, MarginL, \
+MarginR, MarginV, AlphaLevel, Encoding
+Style: Default,Arial,20,16777215,255,0,0,0,0,1,2,2,2,10,10,10,0,1";
+
+/// Section of the file being read.
+enum Section {
+    ScriptInfo,
+    Styles,
+    Events,
+    Other,
+}
+
+/// Parses the cues and metadata of an SSA or ASS file.
+///
+/// `[Script Info]` entries are kept as headers and the lines of the styles section as styles.
+/// Only `Dialogue:` events are read, following the `Format:` line of the `[Events]` section.
+pub(crate) fn parse(text: &str) -> (Vec<Cue>, Metadata) {
+    let mut cues = Vec::new();
+    let mut metadata = Metadata::default();
+    let mut section = Section::Other;
+    let mut fields = EVENT_FIELDS.map(String::from).to_vec();
+
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            section = match line.to_lowercase().as_str() {
+                "[script info]" => Section::ScriptInfo,
+                "[v4+ styles]" | "[v4 styles]" | "[v4 styles+]" => Section::Styles,
+                "[events]" => Section::Events,
+                _ => Section::Other,
+            };
+            continue;
+        }
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        match section {
+            Section::ScriptInfo => {
+                if let Some((key, value)) = line.split_once(':') {
+                    metadata
+                        .headers
+                        .push((key.trim().to_string(), value.trim().to_string()));
+                }
+            }
+            Section::Styles => metadata.styles.push(line.to_string()),
+            Section::Events => {
+                let Some((kind, value)) = line.split_once(':') else {
+                    continue;
+                };
+                match kind.trim().to_lowercase().as_str() {
+                    "format" => {
+                        fields = value
+                            .split(',')
+                            .map(|field| field.trim().to_lowercase())
+                            .collect();
+                    }
+                    "dialogue" => cues.extend(parse_dialogue(value, &fields)),
+                    _ => {}
+                }
+            }
+            Section::Other => {}
+        }
+    }
+
+    (cues, metadata)
+}
+
+/// Parses the values of a `Dialogue:` line. The text is the last field and may contain commas.
+fn parse_dialogue(value: &str, fields: &[String]) -> Option<Cue> {
+    let values = value
+        .trim_start()
+        .splitn(fields.len(), ',')
+        .collect::<Vec<_>>();
+    let field = |name: &str| {
+        fields
+            .iter()
+            .position(|field| field == name)
+            .and_then(|index| values.get(index))
+            .copied()
+    };
+
+    Some(Cue {
+        start: parse_timestamp(field("start")?)?,
+        end: parse_timestamp(field("end")?)?,
+        text: field("text")
+            .unwrap_or_default()
+            .replace("\\N", "\n")
+            .replace("\\n", "\n"),
+        style: field("style")
+            .map(str::trim)
+            .filter(|style| !style.is_empty())
+            .map(|style| style.trim_start_matches('*').to_string()),
+        ..Default::default()
+    })
+}
+
+/// Writes `document` as ASS, or as SSA if `ssa` is `true`.
+///
+/// Headers and styles are kept when the document was read from an SSA or ASS file, otherwise a
+/// `Default` style is written.
+pub(crate) fn write(document: &SubtitleDocument, ssa: bool) -> String {
+    let from_ass = matches!(document.format, Some(SubFormat::Ass | SubFormat::Ssa));
+    let mut text = String::from("[Script Info]\n");
+
+    let headers = if from_ass {
+        document.metadata.headers.as_slice()
+    } else {
+        &[]
+    };
+    for (key, value) in headers
+        .iter()
+        .filter(|(key, _)| !key.eq_ignore_ascii_case("scripttype"))
+    {
+        text.push_str(&format!("{key}: {value}\n"));
+    }
+    text.push_str(if ssa {
+        "ScriptType: v4.00\n"
+    } else {
+        "ScriptType: v4.00+\n"
+    });
+
+    text.push_str(if ssa {
+        "\n[V4 Styles]\n"
+    } else {
+        "\n[V4+ Styles]\n"
+    });
+    if from_ass && !document.metadata.styles.is_empty() {
+        for style in &document.metadata.styles {
+            text.push_str(&format!("{style}\n"));
+        }
+    } else {
+        text.push_str(if ssa { SSA_STYLE } else { ASS_STYLE });
+        text.push('\n');
+    }
+
+    text.push_str("\n[Events]\n");
+    text.push_str(&format!(
+        "Format: {}, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+        if ssa { "Marked" } else { "Layer" }
+    ));
+    for cue in &document.cues {
+        text.push_str(&format!(
+            "Dialogue: {},{},{},{},,0,0,0,,{}\n",
+            if ssa { "Marked=0" } else { "0" },
+            timestamp(cue.start),
+            timestamp(cue.end),
+            cue.style.as_deref().unwrap_or("Default"),
+            cue.text.replace('\n', "\\N")
+        ));
+    }
+    text
+}
+
+/// Formats an SSA/ASS timestamp (`H:MM:SS.cc`).
+fn timestamp(duration: std::time::Duration) -> String {
+    let (hours, minutes, seconds, millis) = clock(duration);
+    format!("{hours}:{minutes:02}:{seconds:02}.{:02}", millis / 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::parse;
+
+    #[test]
+    fn test_parse() {
+        let text = "[Script Info]\n; comment\nTitle: Test\nScriptType: v4.00+\n\n[V4+ Styles]\nFormat: Name, Fontname\nStyle: Top,Arial\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nComment: 0,0:00:00.00,0:00:01.00,Top,,0,0,0,,skipped\nDialogue: 0,0:00:01.50,0:00:03.00,*Top,,0,0,0,,{\\i1}Hi,\\Nthere{\\i0}\n";
+        let (cues, metadata) = parse(text);
+
+        assert_eq!(metadata.headers.len(), 2);
+        assert_eq!(
+            metadata.styles,
+            vec!["Format: Name, Fontname", "Style: Top,Arial"]
+        );
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].start, Duration::from_millis(1500));
+        assert_eq!(cues[0].end, Duration::from_secs(3));
+        assert_eq!(cues[0].style.as_deref(), Some("Top"));
+        assert_eq!(cues[0].text, "{\\i1}Hi,\nthere{\\i0}");
+    }
+}
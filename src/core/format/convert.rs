@@ -0,0 +1,433 @@
+//! Conversion of subtitle files between formats.
+
+use super::{DEFAULT_FPS, SubFormat, SubtitleDocument, detect, valid_fps};
+
+/// Formatting tags kept when mapping the inline formatting of a cue between formats.
+const TAGS: [&str; 3] = ["i", "b", "u"];
+
+/// How the inline formatting of cues is handled by [`convert`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StyleMode {
+    /// Italic, bold and underline are translated to the target format (ASS `{\i1}` overrides,
+    /// `<i>` tags or MicroDVD `{Y:i}` codes) and the ASS `{\anN}` alignment to WebVTT cue
+    /// settings. Other formatting is removed.
+    #[default]
+    Map,
+    /// Every formatting tag and override is removed.
+    Strip,
+    /// The text of the cues is written unchanged.
+    Keep,
+}
+
+/// Options of [`convert`].
+///
+/// # Example
+/// ```
+/// use opensubs::format::{ConvertOptions, StyleMode};
+///
+/// let options = ConvertOptions::default()
+///     .fps(25.0)
+///     .styles(StyleMode::Strip)
+///     .vtt_header("Language", "es");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConvertOptions {
+    /// Frame rate of MicroDVD files without a frame rate header, and of MicroDVD output.
+    fps: Option<f32>,
+    /// Handling of the inline formatting of cues.
+    styles: StyleMode,
+    /// Header entries added to WebVTT output.
+    vtt_headers: Vec<(String, String)>,
+}
+
+impl ConvertOptions {
+    /// Sets the frame rate used to time MicroDVD input without a frame rate header and to
+    /// write MicroDVD output. Accepts [`Subtitle::fps`](crate::Subtitle::fps), which is the
+    /// most reliable value for a downloaded file.
+    ///
+    /// A frame rate header in the file takes precedence; [`DEFAULT_FPS`] is used when none is
+    /// known. A frame rate that is not finite or below 1 (e.g. a `0.0` scraped from the site)
+    /// is ignored.
+    pub fn fps(mut self, fps: impl Into<Option<f32>>) -> Self {
+        self.fps = fps.into().and_then(valid_fps);
+        self
+    }
+
+    /// Sets how the inline formatting of cues is handled.
+    pub fn styles(mut self, styles: StyleMode) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// Adds a header entry (e.g. `Language: es`) to WebVTT output.
+    pub fn vtt_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.vtt_headers.push((key.into(), value.into()));
+        self
+    }
+}
+
+/// Converts the subtitle file `bytes` from the `from` format to the `to` format.
+///
+/// The bytes are decoded as UTF-8, or in the detected encoding with the `encoding` feature
+/// (see [`encoding::decode`](crate::encoding::decode) to decode with a language hint first).
+/// The format is detected from the content when `from` is `None`, so
+/// [`Subtitle::format`](crate::Subtitle::format) can be passed as is. Headers and styles of the
+/// source are only kept when both formats are the same; inline formatting is handled according
+/// to [`ConvertOptions::styles`].
+///
+/// # Errors
+/// - [`Error::UnknownFormat`](crate::Error::UnknownFormat) if `from` is `None` and the format
+///   cannot be detected.
+/// - [`Error::UnsupportedFormat`](crate::Error::UnsupportedFormat) if `from` or `to` is not
+///   supported by [`SubtitleDocument`].
+///
+/// # Example
+/// ```
+/// use opensubs::{SubFormat, format::{self, ConvertOptions}};
+///
+/// let sub = b"{25}{50}{Y:i}Hello|world\n";
+/// let vtt = format::convert(sub, Some(SubFormat::Sub), &SubFormat::Vtt, &ConvertOptions::default().fps(25.0))?;
+/// assert_eq!(vtt, "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n<i>Hello\nworld</i>\n");
+/// # Ok::<(), opensubs::Error>(())
+/// ```
+pub fn convert(
+    bytes: &[u8],
+    from: Option<SubFormat>,
+    to: &SubFormat,
+    options: &ConvertOptions,
+) -> crate::Result<String> {
+    #[cfg(feature = "encoding")]
+    let text = crate::encoding::decode(bytes, None).text;
+    #[cfg(not(feature = "encoding"))]
+    let text = String::from_utf8_lossy(bytes);
+    let from = from
+        .or_else(|| detect(&text))
+        .ok_or(crate::Error::UnknownFormat)?;
+    let mut document =
+        SubtitleDocument::parse_with_fps(&text, from.clone(), options.fps.unwrap_or(DEFAULT_FPS))?;
+
+    let same = from == *to;
+    if !same {
+        document.metadata.headers.clear();
+        document.metadata.styles.clear();
+    }
+    document.metadata.fps = document.metadata.fps.or(options.fps);
+    if *to == SubFormat::Vtt {
+        document
+            .metadata
+            .headers
+            .extend(options.vtt_headers.iter().cloned());
+    }
+
+    for cue in &mut document.cues {
+        if !same {
+            // Style names refer to the styles of the source
+            cue.style = None;
+        }
+        match options.styles {
+            StyleMode::Map if !same => {
+                let (text, alignment) = normalize(&cue.text, &from);
+                cue.text = render(&text, alignment, to);
+                if *to == SubFormat::Vtt && cue.settings.is_none() {
+                    cue.settings = alignment.and_then(vtt_settings);
+                }
+            }
+            StyleMode::Strip => {
+                cue.text = strip_tags(&normalize(&cue.text, &from).0);
+                cue.style = None;
+            }
+            StyleMode::Map | StyleMode::Keep => {}
+        }
+    }
+
+    document.format = Some(to.clone());
+    document.to_format(to)
+}
+
+/// Reduces the formatting of `text` to `<i>`, `<b>` and `<u>` tags and returns it with the
+/// `{\anN}` alignment of the cue, if any.
+///
+/// ASS overrides, MicroDVD `{y:...}` codes and HTML-like tags are understood; any other tag or
+/// override is removed.
+fn normalize(text: &str, from: &SubFormat) -> (String, Option<u8>) {
+    let text = match from {
+        SubFormat::Ass | SubFormat::Ssa => text.replace("\\h", " "),
+        _ => text.to_string(),
+    };
+    let mut alignment = None;
+    let mut cue_closes = Vec::new();
+    let mut lines = Vec::new();
+
+    for line in text.split('\n') {
+        let mut normalized = String::new();
+        let mut line_closes = Vec::new();
+        let mut rest = line;
+
+        while let Some(char) = rest.chars().next() {
+            let close = match char {
+                '{' => rest.find('}'),
+                '<' => rest.find('>'),
+                _ => None,
+            };
+            let Some(close) = close else {
+                normalized.push(char);
+                rest = &rest[char.len_utf8()..];
+                continue;
+            };
+            let code = &rest[1..close];
+            rest = &rest[close + 1..];
+
+            if char == '<' {
+                let name = code.trim_start_matches('/').trim().to_lowercase();
+                if TAGS.contains(&name.as_str()) {
+                    normalized.push_str(&format!("<{}>", code.trim().to_lowercase()));
+                } else if !code.starts_with(|c: char| c.is_alphanumeric() || c == '/') {
+                    // Not a tag, e.g. "a < b > c"
+                    normalized.push_str(&format!("<{code}>"));
+                }
+            } else if let Some(overrides) = code.strip_prefix('\\') {
+                for value in overrides.split('\\') {
+                    if let Some(position) = value.strip_prefix("an") {
+                        alignment = position
+                            .trim()
+                            .parse()
+                            .ok()
+                            .filter(|an| (1..=9).contains(an));
+                    } else if let Some(tag) = ass_tag(value) {
+                        normalized.push_str(&tag);
+                    }
+                }
+            } else if *from == SubFormat::Sub
+                && let Some((kind @ ("y" | "Y"), values)) = code.split_once(':')
+            {
+                for tag in values.split(',').map(|tag| tag.trim().to_lowercase()) {
+                    if TAGS.contains(&tag.as_str()) {
+                        normalized.push_str(&format!("<{tag}>"));
+                        if kind == "Y" {
+                            cue_closes.push(tag);
+                        } else {
+                            line_closes.push(tag);
+                        }
+                    }
+                }
+            } else if *from != SubFormat::Sub {
+                normalized.push_str(&format!("{{{code}}}"));
+            }
+        }
+
+        for tag in line_closes.iter().rev() {
+            normalized.push_str(&format!("</{tag}>"));
+        }
+        lines.push(normalized);
+    }
+
+    let mut text = lines.join("\n");
+    for tag in cue_closes.iter().rev() {
+        text.push_str(&format!("</{tag}>"));
+    }
+    (text, alignment)
+}
+
+/// Translates an ASS override (e.g. `i1`, `b0`) to an opening or closing tag.
+fn ass_tag(value: &str) -> Option<String> {
+    let value = value.trim();
+    let tag = TAGS.into_iter().find(|tag| {
+        value
+            .strip_prefix(tag)
+            .is_some_and(|flag| flag.parse::<u32>().is_ok())
+    })?;
+
+    // Bold also accepts font weights (e.g. `b700`)
+    Some(match &value[1..] {
+        "0" => format!("</{tag}>"),
+        _ => format!("<{tag}>"),
+    })
+}
+
+/// Writes the `<i>`, `<b>` and `<u>` tags and the `alignment` of a normalized text in the
+/// formatting of the `to` format.
+fn render(text: &str, alignment: Option<u8>, to: &SubFormat) -> String {
+    match to {
+        SubFormat::Ass | SubFormat::Ssa => {
+            let mut text = TAGS.into_iter().fold(text.to_string(), |text, tag| {
+                text.replace(&format!("<{tag}>"), &format!("{{\\{tag}1}}"))
+                    .replace(&format!("</{tag}>"), &format!("{{\\{tag}0}}"))
+            });
+            if let Some(alignment) = alignment.filter(|_| *to == SubFormat::Ass) {
+                text.insert_str(0, &format!("{{\\an{alignment}}}"));
+            }
+            text
+        }
+        SubFormat::Srt => match alignment {
+            Some(alignment) if alignment != 2 => format!("{{\\an{alignment}}}{text}"),
+            _ => text.to_string(),
+        },
+        SubFormat::Vtt => text.to_string(),
+        SubFormat::Sub => {
+            // Only the formatting of the whole cue can be written
+            let mut text = text;
+            let mut tags = Vec::new();
+            while let Some(tag) = TAGS.into_iter().find(|tag| {
+                text.starts_with(&format!("<{tag}>")) && text.ends_with(&format!("</{tag}>"))
+            }) {
+                text = &text[tag.len() + 2..text.len() - tag.len() - 3];
+                tags.push(tag);
+            }
+            match tags.is_empty() {
+                true => strip_tags(text),
+                false => format!("{{Y:{}}}{}", tags.join(","), strip_tags(text)),
+            }
+        }
+        _ => strip_tags(text),
+    }
+}
+
+/// Removes the `<i>`, `<b>` and `<u>` tags of a normalized text.
+fn strip_tags(text: &str) -> String {
+    TAGS.into_iter().fold(text.to_string(), |text, tag| {
+        text.replace(&format!("<{tag}>"), "")
+            .replace(&format!("</{tag}>"), "")
+    })
+}
+
+/// Translates an ASS numpad alignment to WebVTT cue settings.
+fn vtt_settings(alignment: u8) -> Option<String> {
+    let line = match alignment {
+        7..=9 => Some("line:0"),
+        4..=6 => Some("line:50%"),
+        _ => None,
+    };
+    let align = match alignment % 3 {
+        1 => Some("align:start"),
+        0 => Some("align:end"),
+        _ => None,
+    };
+
+    let settings = [line, align].into_iter().flatten().collect::<Vec<_>>();
+    (!settings.is_empty()).then(|| settings.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConvertOptions, StyleMode, convert};
+    use crate::{Error, core::model::SubFormat};
+
+    const ASS: &str = "[Script Info]\nScriptType: v4.00+\n\n[V4+ Styles]\nFormat: Name, Fontname\nStyle: Top,Arial\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: 0,0:00:01.00,0:00:02.00,Top,,0,0,0,,{\\an8\\i1}Hello{\\i0}\\h{\\fad(200,0)}world\n";
+
+    #[test]
+    fn test_convert_ass_styles() {
+        let options = ConvertOptions::default();
+
+        let srt = convert(
+            ASS.as_bytes(),
+            Some(SubFormat::Ass),
+            &SubFormat::Srt,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            srt,
+            "1\n00:00:01,000 --> 00:00:02,000\n{\\an8}<i>Hello</i> world\n"
+        );
+
+        let vtt = convert(ASS.as_bytes(), None, &SubFormat::Vtt, &options).unwrap();
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n00:00:01.000 --> 00:00:02.000 line:0\n<i>Hello</i> world\n"
+        );
+
+        let options = options.styles(StyleMode::Strip);
+        let srt = convert(ASS.as_bytes(), None, &SubFormat::Srt, &options).unwrap();
+        assert!(srt.ends_with("\nHello world\n"));
+
+        let ass = convert(ASS.as_bytes(), None, &SubFormat::Ass, &options).unwrap();
+        assert!(ass.contains("Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello world\n"));
+    }
+
+    #[test]
+    fn test_convert_keeps_same_format() {
+        let options = ConvertOptions::default();
+
+        let ass = convert(ASS.as_bytes(), None, &SubFormat::Ass, &options).unwrap();
+        assert!(ass.contains("Style: Top,Arial\n"));
+        assert!(ass.contains(",Top,,0,0,0,,{\\an8\\i1}Hello{\\i0}\\h{\\fad(200,0)}world\n"));
+    }
+
+    #[test]
+    fn test_convert_to_microdvd_and_ass() {
+        let srt = b"1\n00:00:01,000 --> 00:00:02,000\n<i>Hello\nworld</i>\n\n2\n00:00:03,000 --> 00:00:04,000\n<font color=\"red\"><b>Bye</b></font>\n";
+        let options = ConvertOptions::default().fps(25.0);
+
+        let sub = convert(srt, Some(SubFormat::Srt), &SubFormat::Sub, &options).unwrap();
+        assert_eq!(
+            sub,
+            "{1}{1}25\n{25}{50}{Y:i}Hello|world\n{75}{100}{Y:b}Bye\n"
+        );
+
+        let ass = convert(srt, None, &SubFormat::Ass, &options).unwrap();
+        assert!(ass.contains(",Default,,0,0,0,,{\\i1}Hello\\Nworld{\\i0}\n"));
+        assert!(ass.contains(",Default,,0,0,0,,{\\b1}Bye{\\b0}\n"));
+    }
+
+    #[test]
+    fn test_convert_microdvd_fps() {
+        let sub = b"{50}{100}{y:i}Hello|world\n";
+        let vtt = |fps| {
+            convert(
+                sub,
+                Some(SubFormat::Sub),
+                &SubFormat::Vtt,
+                &ConvertOptions::default()
+                    .fps(fps)
+                    .vtt_header("Language", "en"),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            vtt(Some(25.0)),
+            "WEBVTT\nLanguage: en\n\n00:00:02.000 --> 00:00:04.000\n<i>Hello</i>\nworld\n"
+        );
+        assert!(vtt(None).contains("\n00:00:02.085 --> 00:00:04.170\n"));
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_convert_decodes_encoding() {
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in "1\n00:00:01,000 --> 00:00:02,000\nÇa va?\n".encode_utf16() {
+            bytes.extend(unit.to_le_bytes());
+        }
+
+        let vtt = convert(&bytes, None, &SubFormat::Vtt, &ConvertOptions::default()).unwrap();
+        assert_eq!(vtt, "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\nÇa va?\n");
+    }
+
+    #[test]
+    fn test_convert_invalid_fps() {
+        for fps in [0.0, -25.0, f32::NAN, f32::INFINITY] {
+            let srt = convert(
+                b"{240}{480}Hi\n",
+                Some(SubFormat::Sub),
+                &SubFormat::Srt,
+                &ConvertOptions::default().fps(fps),
+            )
+            .unwrap();
+            assert_eq!(srt, "1\n00:00:10,010 --> 00:00:20,020\nHi\n", "{fps}");
+        }
+    }
+
+    #[test]
+    fn test_convert_errors() {
+        let options = ConvertOptions::default();
+
+        assert!(matches!(
+            convert(b"hello", None, &SubFormat::Srt, &options),
+            Err(Error::UnknownFormat)
+        ));
+        assert!(matches!(
+            convert(b"{1}{2}Hi", None, &SubFormat::Smi, &options),
+            Err(Error::UnsupportedFormat(SubFormat::Smi))
+        ));
+    }
+}
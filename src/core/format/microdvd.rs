@@ -0,0 +1,133 @@
+//! MicroDVD (`.sub`) reader and writer.
+
+use std::time::Duration;
+
+use super::{Cue, DEFAULT_FPS, Metadata, SubtitleDocument, valid_fps};
+
+/// Parses a `{start}{end}text` line into its frames and text.
+///
+/// An empty end frame (`{start}{}text`) ends the cue at its start frame.
+pub(crate) fn parse_line(line: &str) -> Option<(u64, u64, &str)> {
+    let (start, rest) = line.trim().strip_prefix('{')?.split_once('}')?;
+    let (end, text) = rest.strip_prefix('{')?.split_once('}')?;
+    let start = start.trim().parse().ok()?;
+    let end = match end.trim() {
+        "" => start,
+        end => end.parse().ok()?,
+    };
+
+    Some((start, end, text))
+}
+
+/// Parses the cues of a MicroDVD file.
+///
+/// A first line `{1}{1}<fps>` sets the frame rate, otherwise `default_fps` is used. Frame rates
+/// that are not finite or below 1 are ignored, as are cues whose time cannot be represented.
+/// The `|` separating lines are replaced by `\n`.
+pub(crate) fn parse(text: &str, default_fps: f32) -> (Vec<Cue>, Metadata) {
+    let mut lines = text.lines().filter_map(parse_line).peekable();
+    let mut metadata = Metadata::default();
+
+    if let Some((_, _, header)) = lines.next_if(|(start, end, text)| {
+        *start <= 1 && *end <= 1 && text.trim().parse::<f32>().is_ok()
+    }) {
+        metadata.fps = header.trim().parse().ok().and_then(valid_fps);
+    }
+    let fps = metadata
+        .fps
+        .or_else(|| valid_fps(default_fps))
+        .unwrap_or(DEFAULT_FPS);
+    let time = |frame: u64| Duration::try_from_secs_f64(frame as f64 / f64::from(fps)).ok();
+
+    let cues = lines
+        .filter_map(|(start, end, text)| {
+            Some(Cue {
+                start: time(start)?,
+                end: time(end)?,
+                text: text.trim_end().replace('|', "\n"),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    (cues, metadata)
+}
+
+/// Writes `document` as MicroDVD, with a frame rate header line.
+pub(crate) fn write(document: &SubtitleDocument) -> String {
+    let fps = document
+        .metadata
+        .fps
+        .and_then(valid_fps)
+        .unwrap_or(DEFAULT_FPS);
+    let frame = |duration: Duration| (duration.as_secs_f64() * f64::from(fps)).round() as u64;
+
+    let mut text = format!("{{1}}{{1}}{fps}\n");
+    for cue in &document.cues {
+        text.push_str(&format!(
+            "{{{}}}{{{}}}{}\n",
+            frame(cue.start),
+            frame(cue.end),
+            cue.text.replace('\n', "|")
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{DEFAULT_FPS, parse, parse_line, write};
+    use crate::core::format::{SubFormat, SubtitleDocument};
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(parse_line("{10}{20}Hi"), Some((10, 20, "Hi")));
+        assert_eq!(parse_line("{10}{}Hi"), Some((10, 10, "Hi")));
+        assert_eq!(parse_line("{y:i}Hi"), None);
+        assert_eq!(parse_line("00:00:01,000 --> 00:00:02,000"), None);
+    }
+
+    #[test]
+    fn test_parse_without_header() {
+        let (cues, metadata) = parse("{25}{50}Hello\n", 25.0);
+
+        assert_eq!(metadata.fps, None);
+        assert_eq!(cues[0].end, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_parse_invalid_fps() {
+        let (cues, metadata) = parse(
+            "{1}{1}0.000001\n{18446744073709551615}{1}Hi\n{25}{50}Ok\n",
+            0.0,
+        );
+
+        assert_eq!(metadata.fps, None);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[1].text, "Ok");
+        assert_eq!(
+            cues[1].start,
+            Duration::from_secs_f64(25.0 / f64::from(DEFAULT_FPS))
+        );
+    }
+
+    #[test]
+    fn test_parse_and_write() {
+        let text = "{1}{1}25\n{25}{50}{y:i}Hello|world\nnot a cue\n{75}{100}Bye\n";
+        let (cues, metadata) = parse(text, 10.0);
+
+        assert_eq!(metadata.fps, Some(25.0));
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].start, Duration::from_secs(1));
+        assert_eq!(cues[0].end, Duration::from_secs(2));
+        assert_eq!(cues[0].text, "{y:i}Hello\nworld");
+
+        let document = SubtitleDocument::parse(text, SubFormat::Sub).unwrap();
+        assert_eq!(
+            write(&document),
+            "{1}{1}25\n{25}{50}{y:i}Hello|world\n{75}{100}Bye\n"
+        );
+    }
+}
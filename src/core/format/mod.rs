@@ -0,0 +1,330 @@
+//! Parsing and writing of subtitle files.
+//!
+//! SubRip (`.srt`), WebVTT (`.vtt`), SubStation Alpha (`.ssa`), Advanced SubStation Alpha
+//! (`.ass`) and MicroDVD (`.sub`) files are read into a common [`SubtitleDocument`] made of
+//! [`Cue`]s, and written back in any of those formats. Parsers are tolerant of the malformed
+//! files commonly found on opensubtitles.org: missing indexes or blank lines, `.` instead of `,`
+//! in timestamps, missing hours, stray lines, BOMs and `\r\n` line endings.
+//!
+//! [`convert`] turns a downloaded file into another format in one call, mapping the inline
+//! formatting and the MicroDVD frame rate. Badly timed files are fixed with
+//! [`SubtitleDocument::delay`], [`SubtitleDocument::advance`], [`SubtitleDocument::retime_fps`]
+//! and [`SubtitleDocument::resync`], then written back in their original format with
+//! `to_string`.
+//!
+//! # Example
+//! ```
+//! use opensubs::{SubFormat, format::{self, SubtitleDocument}};
+//!
+//! let srt = "1\n00:00:01,000 --> 00:00:02,500\nHello\n";
+//! let document = SubtitleDocument::parse(srt, format::detect(srt).unwrap()).unwrap();
+//! assert_eq!(document.cues[0].text, "Hello");
+//!
+//! let vtt = document.to_format(&SubFormat::Vtt).unwrap();
+//! assert!(vtt.starts_with("WEBVTT"));
+//! ```
+
+mod ass;
+mod convert;
+mod microdvd;
+mod srt;
+mod timing;
+mod vtt;
+
+use std::time::Duration;
+
+use super::model::SubFormat;
+
+pub use convert::{ConvertOptions, StyleMode, convert};
+
+/// A subtitle file in a format-independent representation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubtitleDocument {
+    /// Format the document was parsed from, used by its `Display` implementation.
+    pub format: Option<SubFormat>,
+    /// Cues in file order.
+    pub cues: Vec<Cue>,
+    /// Data of the file not bound to a cue.
+    pub metadata: Metadata,
+}
+
+/// A subtitle shown on screen between two instants.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cue {
+    /// Instant the cue is shown.
+    pub start: Duration,
+    /// Instant the cue is hidden.
+    pub end: Duration,
+    /// Text of the cue, lines separated by `\n`. Inline formatting of the source is kept
+    /// (e.g. `<i>` tags or ASS `{\i1}` overrides).
+    pub text: String,
+    /// Name of the ASS/SSA style of the cue.
+    pub style: Option<String>,
+    /// WebVTT cue identifier.
+    pub identifier: Option<String>,
+    /// WebVTT cue settings (e.g. `"position:10% align:start"`).
+    pub settings: Option<String>,
+}
+
+/// Data of a subtitle file not bound to a cue.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    /// Header entries: the ASS/SSA `[Script Info]` or the WebVTT header lines, as `(key, value)`.
+    pub headers: Vec<(String, String)>,
+    /// Style definitions: the ASS/SSA style lines (including their `Format:` line) or the
+    /// WebVTT `STYLE` and `REGION` blocks.
+    pub styles: Vec<String>,
+    /// Frame rate of frame-based formats (MicroDVD).
+    pub fps: Option<f32>,
+}
+
+/// Frame rate assumed for MicroDVD files without a frame rate header.
+pub const DEFAULT_FPS: f32 = 23.976;
+
+/// Lowest frame rate accepted; frame numbers divided by smaller values overflow the cue times.
+const MIN_FPS: f32 = 1.0;
+
+impl SubtitleDocument {
+    /// Parses `text` in the given `format`.
+    ///
+    /// Lines that cannot be understood are skipped, so the result may have fewer cues than the
+    /// file if it is badly malformed.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnsupportedFormat`](crate::Error::UnsupportedFormat) for formats other
+    /// than SubRip, WebVTT, SSA, ASS and MicroDVD.
+    pub fn parse(text: &str, format: SubFormat) -> crate::Result<Self> {
+        Self::parse_with_fps(text, format, DEFAULT_FPS)
+    }
+
+    /// Parses `text` in the given `format`, timing MicroDVD files without a frame rate header
+    /// at `fps`.
+    pub(crate) fn parse_with_fps(text: &str, format: SubFormat, fps: f32) -> crate::Result<Self> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let (cues, metadata) = match format {
+            SubFormat::Srt => (srt::parse(text), Metadata::default()),
+            SubFormat::Vtt => vtt::parse(text),
+            SubFormat::Ass | SubFormat::Ssa => ass::parse(text),
+            SubFormat::Sub => microdvd::parse(text, fps),
+            format => return Err(crate::Error::UnsupportedFormat(format)),
+        };
+
+        Ok(Self {
+            format: Some(format),
+            cues,
+            metadata,
+        })
+    }
+
+    /// Writes the document in the given `format`.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnsupportedFormat`](crate::Error::UnsupportedFormat) for formats other
+    /// than SubRip, WebVTT, SSA, ASS and MicroDVD.
+    pub fn to_format(&self, format: &SubFormat) -> crate::Result<String> {
+        Ok(match format {
+            SubFormat::Srt => srt::write(self),
+            SubFormat::Vtt => vtt::write(self),
+            SubFormat::Ass => ass::write(self, false),
+            SubFormat::Ssa => ass::write(self, true),
+            SubFormat::Sub => microdvd::write(self),
+            format => return Err(crate::Error::UnsupportedFormat(format.clone())),
+        })
+    }
+}
+
+impl std::fmt::Display for SubtitleDocument {
+    /// Writes the document in the format it was parsed from (SubRip if unknown or unsupported).
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let text = self
+            .format
+            .as_ref()
+            .and_then(|format| self.to_format(format).ok())
+            .unwrap_or_else(|| srt::write(self));
+        f.write_str(&text)
+    }
+}
+
+/// Guesses the format of a subtitle file from its content.
+///
+/// Returns `None` if the content does not look like any supported format.
+pub fn detect(text: &str) -> Option<SubFormat> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text).trim_start();
+    let first_line = text.lines().next().unwrap_or_default();
+
+    if first_line.starts_with("WEBVTT") {
+        Some(SubFormat::Vtt)
+    } else if first_line.eq_ignore_ascii_case("[script info]") {
+        let text = text.to_lowercase();
+        if text.contains("[v4+ styles]") || text.contains("scripttype: v4.00+") {
+            Some(SubFormat::Ass)
+        } else {
+            Some(SubFormat::Ssa)
+        }
+    } else if microdvd::parse_line(first_line).is_some() {
+        Some(SubFormat::Sub)
+    } else if text.lines().any(|line| parse_timing(line).is_some()) {
+        Some(SubFormat::Srt)
+    } else {
+        None
+    }
+}
+
+/// Parses a timing line `start --> end [settings]`, as used by SubRip and WebVTT.
+///
+/// Returns the start, the end and the trimmed remainder of the line.
+pub(crate) fn parse_timing(line: &str) -> Option<(Duration, Duration, &str)> {
+    let (start, rest) = line.split_once("-->")?;
+    let rest = rest.trim_start();
+    let (end, settings) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+    Some((
+        parse_timestamp(start)?,
+        parse_timestamp(end)?,
+        settings.trim(),
+    ))
+}
+
+/// Parses a `[HH:]MM:SS[.,]fff` timestamp, tolerating one-digit fields and any fraction length.
+pub(crate) fn parse_timestamp(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (clock, fraction) = match value.rfind(['.', ',']) {
+        Some(index) => (&value[..index], &value[index + 1..]),
+        None => (value, ""),
+    };
+
+    let mut seconds = 0u64;
+    let parts = clock.split(':').collect::<Vec<_>>();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    for part in parts {
+        seconds = seconds
+            .checked_mul(60)?
+            .checked_add(part.trim().parse::<u64>().ok()?)?;
+    }
+
+    let nanos = match fraction {
+        "" => 0,
+        fraction if fraction.bytes().all(|byte| byte.is_ascii_digit()) => {
+            let digits = &fraction[..fraction.len().min(9)];
+            digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32)
+        }
+        _ => return None,
+    };
+
+    Some(Duration::new(seconds, nanos))
+}
+
+/// Returns `fps` if it is a usable frame rate: finite and at least [`MIN_FPS`].
+pub(crate) fn valid_fps(fps: f32) -> Option<f32> {
+    (fps.is_finite() && fps >= MIN_FPS).then_some(fps)
+}
+
+/// Splits a duration into hours, minutes, seconds and milliseconds.
+pub(crate) fn clock(duration: Duration) -> (u64, u64, u64, u32) {
+    let seconds = duration.as_secs();
+    (
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        duration.subsec_millis(),
+    )
+}
+
+/// Trims the blank lines at both ends of `lines` and joins them with `\n`.
+pub(crate) fn join_text(lines: &[&str]) -> String {
+    let start = lines.iter().position(|line| !line.trim().is_empty());
+    let end = lines.iter().rposition(|line| !line.trim().is_empty());
+
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end]
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{SubtitleDocument, detect, parse_timestamp, parse_timing};
+    use crate::core::model::SubFormat;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(
+            parse_timestamp("01:02:03,456"),
+            Some(Duration::from_millis(3_723_456))
+        );
+        assert_eq!(
+            parse_timestamp("2:03.5"),
+            Some(Duration::from_millis(123_500))
+        );
+        assert_eq!(parse_timestamp("00:00:07"), Some(Duration::from_secs(7)));
+        assert_eq!(parse_timestamp("aa:00:07"), None);
+        assert_eq!(parse_timestamp("9999999999999999:99:00,000"), None);
+        assert_eq!(
+            parse_timing("00:00:01.000 --> 00:00:02.000 align:start"),
+            Some((
+                Duration::from_secs(1),
+                Duration::from_secs(2),
+                "align:start"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect("\u{feff}WEBVTT\n\n"), Some(SubFormat::Vtt));
+        assert_eq!(
+            detect("[Script Info]\nScriptType: v4.00+\n"),
+            Some(SubFormat::Ass)
+        );
+        assert_eq!(
+            detect("[Script Info]\nScriptType: v4.00\n"),
+            Some(SubFormat::Ssa)
+        );
+        assert_eq!(detect("{1}{1}25\n{10}{20}Hi"), Some(SubFormat::Sub));
+        assert_eq!(
+            detect("1\n00:00:01,000 --> 00:00:02,000\nHi"),
+            Some(SubFormat::Srt)
+        );
+        assert_eq!(detect("hello"), None);
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        assert!(SubtitleDocument::parse("", SubFormat::Smi).is_err());
+        assert!(
+            SubtitleDocument::default()
+                .to_format(&SubFormat::Txt)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_between_formats() {
+        let srt = "1\n00:00:01,000 --> 00:00:02,500\nHello\nworld\n\n2\n00:01:00,250 --> 00:01:03,000\n<i>Bye</i>\n";
+        let document = SubtitleDocument::parse(srt, SubFormat::Srt).unwrap();
+
+        for format in [
+            SubFormat::Srt,
+            SubFormat::Vtt,
+            SubFormat::Ass,
+            SubFormat::Ssa,
+        ] {
+            let text = document.to_format(&format).unwrap();
+            let parsed = SubtitleDocument::parse(&text, format.clone()).unwrap();
+            assert_eq!(parsed.cues.len(), 2, "{format:?}");
+            for (parsed, cue) in parsed.cues.iter().zip(&document.cues) {
+                assert_eq!((parsed.start, parsed.end), (cue.start, cue.end));
+                assert_eq!(parsed.text, cue.text);
+            }
+        }
+        assert_eq!(document.to_string(), srt);
+    }
+}
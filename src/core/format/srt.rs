@@ -0,0 +1,100 @@
+//! SubRip (`.srt`) reader and writer.
+
+use super::{Cue, SubtitleDocument, clock, join_text, parse_timing};
+
+/// Parses the cues of a SubRip file.
+///
+/// A cue starts at every timing line, so missing blank lines or indexes are tolerated. The index
+/// line preceding a timing line is dropped and the lines before the first cue are ignored.
+pub(crate) fn parse(text: &str) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    let mut current: Option<(Cue, Vec<&str>)> = None;
+
+    for line in text.lines() {
+        let Some((start, end, _)) = parse_timing(line) else {
+            if let Some((_, lines)) = current.as_mut() {
+                lines.push(line);
+            }
+            continue;
+        };
+
+        if let Some((cue, mut lines)) = current.take() {
+            while lines.last().is_some_and(|line| line.trim().is_empty()) {
+                lines.pop();
+            }
+            if lines.last().is_some_and(|line| is_index(line)) {
+                lines.pop();
+            }
+            cues.push(finish(cue, &lines));
+        }
+
+        let cue = Cue {
+            start,
+            end,
+            ..Default::default()
+        };
+        current = Some((cue, Vec::new()));
+    }
+
+    if let Some((cue, lines)) = current {
+        cues.push(finish(cue, &lines));
+    }
+    cues
+}
+
+/// Sets the text of `cue` from its lines.
+fn finish(mut cue: Cue, lines: &[&str]) -> Cue {
+    cue.text = join_text(lines);
+    cue
+}
+
+/// Returns `true` if `line` is a cue index.
+fn is_index(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && line.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// Writes the cues of `document` as SubRip, renumbering them from 1.
+pub(crate) fn write(document: &SubtitleDocument) -> String {
+    document
+        .cues
+        .iter()
+        .enumerate()
+        .map(|(index, cue)| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                index + 1,
+                timestamp(cue.start),
+                timestamp(cue.end),
+                cue.text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Formats a SubRip timestamp (`HH:MM:SS,mmm`).
+fn timestamp(duration: std::time::Duration) -> String {
+    let (hours, minutes, seconds, millis) = clock(duration);
+    format!("{hours:02}:{minutes:02}:{seconds:02},{millis:03}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::parse;
+
+    #[test]
+    fn test_parse_malformed() {
+        let text = "\u{feff}garbage\r\n1\r\n00:00:01.000 --> 00:00:02,000 X1:10 X2:20\r\nHello\r\n2\n0:03,5 --> 0:04,0\nNo blank line\n\n\nstill text\n\n3\n00:00:05,000 --> 00:00:06,000\n\n";
+        let cues = parse(text);
+
+        assert_eq!(cues.len(), 3);
+        assert_eq!(cues[0].text, "Hello");
+        assert_eq!(cues[0].start, Duration::from_secs(1));
+        assert_eq!(cues[1].start, Duration::from_millis(3500));
+        assert_eq!(cues[1].text, "No blank line\n\n\nstill text");
+        assert_eq!(cues[2].text, "");
+    }
+}
@@ -0,0 +1,210 @@
+//! Timing operations of a [`SubtitleDocument`].
+
+use std::time::Duration;
+
+use super::{SubFormat, SubtitleDocument, valid_fps};
+
+impl SubtitleDocument {
+    /// Shifts every cue later by `by`, for subtitles shown too early. Cue times saturate at
+    /// [`Duration::MAX`].
+    pub fn delay(&mut self, by: Duration) -> &mut Self {
+        for cue in &mut self.cues {
+            cue.start = cue.start.saturating_add(by);
+            cue.end = cue.end.saturating_add(by);
+        }
+        self
+    }
+
+    /// Shifts every cue earlier by `by`, for subtitles shown too late. Cues reaching the
+    /// beginning of the video are clamped to zero.
+    pub fn advance(&mut self, by: Duration) -> &mut Self {
+        for cue in &mut self.cues {
+            cue.start = cue.start.saturating_sub(by);
+            cue.end = cue.end.saturating_sub(by);
+        }
+        self
+    }
+
+    /// Rescales the cues of a subtitle made for a `from` frame rate video (e.g. `23.976`) to
+    /// play along a `to` frame rate one (e.g. `25.0`).
+    ///
+    /// MicroDVD documents keep their frames and take `to` as their frame rate.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidFps`](crate::Error::InvalidFps), leaving the document
+    /// unchanged, if a frame rate is not finite or below 1.
+    ///
+    /// # Example
+    /// ```
+    /// use opensubs::{SubFormat, SubtitleDocument};
+    ///
+    /// let mut document = SubtitleDocument::parse("1\n00:00:25,000 --> 00:00:50,000\nHi\n", SubFormat::Srt)?;
+    /// document.retime_fps(25.0, 23.976)?;
+    /// assert_eq!(document.to_string(), "1\n00:00:26,068 --> 00:00:52,135\nHi\n");
+    /// # Ok::<(), opensubs::Error>(())
+    /// ```
+    pub fn retime_fps(&mut self, from: f32, to: f32) -> crate::Result<&mut Self> {
+        let fps = |fps| valid_fps(fps).ok_or(crate::Error::InvalidFps(fps));
+        let ratio = f64::from(fps(from)?) / f64::from(fps(to)?);
+
+        self.map_times(|seconds| seconds * ratio);
+        if self.format == Some(SubFormat::Sub) {
+            self.metadata.fps = Some(to);
+        }
+        Ok(self)
+    }
+
+    /// Resynchronizes the cues linearly so that the cue at index `first.0` starts at `first.1`
+    /// and the cue at index `second.0` starts at `second.1`.
+    ///
+    /// Both the offset and the speed of the cues are corrected, which fixes subtitles made for
+    /// another cut or frame rate of the video. Picking a cue near the beginning and another near
+    /// the end gives the best results.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidResync`](crate::Error::InvalidResync) if an index is out of
+    /// range or both cues start at the same time.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use opensubs::{SubFormat, SubtitleDocument};
+    ///
+    /// let srt = "1\n00:00:10,000 --> 00:00:11,000\nHi\n\n2\n00:01:00,000 --> 00:01:01,000\nBye\n";
+    /// let mut document = SubtitleDocument::parse(srt, SubFormat::Srt)?;
+    /// document.resync((0, Duration::from_secs(12)), (1, Duration::from_secs(72)))?;
+    /// assert_eq!(document.cues[1].end, Duration::from_millis(73_200));
+    /// # Ok::<(), opensubs::Error>(())
+    /// ```
+    pub fn resync(
+        &mut self,
+        first: (usize, Duration),
+        second: (usize, Duration),
+    ) -> crate::Result<&mut Self> {
+        let start = |index: usize| {
+            self.cues
+                .get(index)
+                .map(|cue| cue.start.as_secs_f64())
+                .ok_or_else(|| {
+                    crate::Error::InvalidResync(format!(
+                        "cue {index} out of range ({} cues)",
+                        self.cues.len()
+                    ))
+                })
+        };
+        let (from_first, from_second) = (start(first.0)?, start(second.0)?);
+        if from_first == from_second {
+            return Err(crate::Error::InvalidResync(format!(
+                "cues {} and {} start at the same time",
+                first.0, second.0
+            )));
+        }
+
+        let (to_first, to_second) = (first.1.as_secs_f64(), second.1.as_secs_f64());
+        let scale = (to_second - to_first) / (from_second - from_first);
+        let offset = to_first - scale * from_first;
+
+        self.map_times(|seconds| scale * seconds + offset);
+        Ok(self)
+    }
+
+    /// Applies `map` to the start and end of every cue, in seconds. Results are rounded to the
+    /// millisecond and clamped to zero.
+    fn map_times(&mut self, map: impl Fn(f64) -> f64) {
+        let apply = |time: Duration| {
+            let millis = (map(time.as_secs_f64()) * 1000.0).round().max(0.0);
+            Duration::from_millis(millis as u64)
+        };
+
+        for cue in &mut self.cues {
+            cue.start = apply(cue.start);
+            cue.end = apply(cue.end);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{Error, core::format::SubtitleDocument, core::model::SubFormat};
+
+    const SRT: &str =
+        "1\n00:00:01,000 --> 00:00:02,000\nHello\n\n2\n00:00:10,000 --> 00:00:12,500\nBye\n";
+
+    #[test]
+    fn test_shift() {
+        let mut document = SubtitleDocument::parse(SRT, SubFormat::Srt).unwrap();
+
+        document.delay(Duration::from_millis(1500));
+        assert_eq!(
+            document.to_string(),
+            "1\n00:00:02,500 --> 00:00:03,500\nHello\n\n2\n00:00:11,500 --> 00:00:14,000\nBye\n"
+        );
+
+        document.delay(Duration::MAX);
+        assert_eq!(document.cues[1].end, Duration::MAX);
+
+        let mut document = SubtitleDocument::parse(SRT, SubFormat::Srt).unwrap();
+        document.delay(Duration::from_millis(1500));
+        document.advance(Duration::from_secs(3));
+        assert_eq!(document.cues[0].start, Duration::ZERO);
+        assert_eq!(document.cues[0].end, Duration::from_millis(500));
+        assert_eq!(document.cues[1].start, Duration::from_millis(8500));
+    }
+
+    #[test]
+    fn test_retime_fps_keeps_microdvd_frames() {
+        let sub = "{1}{1}23.976\n{240}{480}Hello\n";
+        let mut document = SubtitleDocument::parse(sub, SubFormat::Sub).unwrap();
+        let start = document.cues[0].start;
+
+        document.retime_fps(23.976, 25.0).unwrap();
+        assert!(document.cues[0].start < start);
+        assert_eq!(document.to_string(), "{1}{1}25\n{240}{480}Hello\n");
+    }
+
+    #[test]
+    fn test_retime_fps_invalid() {
+        let mut document =
+            SubtitleDocument::parse("{1}{1}25\n{25}{50}Hi\n", SubFormat::Sub).unwrap();
+        let original = document.clone();
+
+        for (from, to) in [
+            (25.0, 0.0),
+            (0.0, 25.0),
+            (f32::NAN, 25.0),
+            (25.0, f32::INFINITY),
+        ] {
+            assert!(matches!(
+                document.retime_fps(from, to),
+                Err(Error::InvalidFps(_))
+            ));
+        }
+        assert_eq!(document, original);
+    }
+
+    #[test]
+    fn test_resync() {
+        let mut document = SubtitleDocument::parse(SRT, SubFormat::Srt).unwrap();
+
+        document
+            .resync((0, Duration::from_secs(3)), (1, Duration::from_secs(21)))
+            .unwrap();
+        let times = document
+            .cues
+            .iter()
+            .map(|cue| (cue.start.as_millis(), cue.end.as_millis()))
+            .collect::<Vec<_>>();
+        assert_eq!(times, vec![(3000, 5000), (21000, 26000)]);
+
+        assert!(matches!(
+            document.resync((0, Duration::ZERO), (2, Duration::ZERO)),
+            Err(Error::InvalidResync(_))
+        ));
+        assert!(matches!(
+            document.resync((1, Duration::ZERO), (1, Duration::from_secs(1))),
+            Err(Error::InvalidResync(_))
+        ));
+    }
+}
@@ -0,0 +1,167 @@
+//! WebVTT (`.vtt`) reader and writer.
+
+use super::{Cue, Metadata, SubFormat, SubtitleDocument, clock, join_text, parse_timing};
+
+/// Parses the cues and metadata of a WebVTT file.
+///
+/// The file is read by blocks separated by blank lines. The first block is the header,
+/// `STYLE` and `REGION` blocks are kept as styles and `NOTE` blocks are skipped. A block
+/// without timing line following a cue is appended to its text.
+pub(crate) fn parse(text: &str) -> (Vec<Cue>, Metadata) {
+    let mut cues: Vec<Cue> = Vec::new();
+    let mut metadata = Metadata::default();
+
+    for (index, block) in blocks(text).into_iter().enumerate() {
+        let first = block[0].trim();
+
+        if index == 0 && first.starts_with("WEBVTT") {
+            metadata.headers = block[1..]
+                .iter()
+                .map(|line| match line.split_once(':') {
+                    Some((key, value)) => (key.trim().to_string(), value.trim().to_string()),
+                    None => (line.trim().to_string(), String::new()),
+                })
+                .collect();
+            continue;
+        }
+        if first.starts_with("NOTE") {
+            continue;
+        }
+        if first.starts_with("STYLE") || first.starts_with("REGION") {
+            metadata.styles.push(block.join("\n"));
+            continue;
+        }
+
+        let Some(timing) = block.iter().position(|line| parse_timing(line).is_some()) else {
+            // Blank lines inside the text of a cue
+            if let Some(cue) = cues.last_mut() {
+                cue.text = format!("{}\n\n{}", cue.text, join_text(&block));
+            }
+            continue;
+        };
+
+        let (start, end, settings) = parse_timing(block[timing]).expect("timing line");
+        cues.push(Cue {
+            start,
+            end,
+            text: join_text(&block[timing + 1..]),
+            identifier: timing
+                .checked_sub(1)
+                .map(|identifier| block[identifier].trim().to_string()),
+            settings: (!settings.is_empty()).then(|| settings.to_string()),
+            ..Default::default()
+        });
+    }
+
+    (cues, metadata)
+}
+
+/// Splits `text` into blocks of non-blank lines.
+fn blocks(text: &str) -> Vec<Vec<&str>> {
+    let mut blocks: Vec<Vec<&str>> = Vec::new();
+    let mut block = Vec::new();
+    for line in text.lines() {
+        if !line.trim().is_empty() {
+            block.push(line);
+        } else if !block.is_empty() {
+            blocks.push(std::mem::take(&mut block));
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Writes `document` as WebVTT.
+///
+/// Headers are kept when the document was read from a WebVTT file.
+pub(crate) fn write(document: &SubtitleDocument) -> String {
+    let mut text = String::from("WEBVTT\n");
+    let headers = match document.format {
+        Some(SubFormat::Vtt) => document.metadata.headers.as_slice(),
+        _ => &[],
+    };
+    for (key, value) in headers {
+        if value.is_empty() {
+            text.push_str(&format!("{key}\n"));
+        } else {
+            text.push_str(&format!("{key}: {value}\n"));
+        }
+    }
+
+    for style in document
+        .metadata
+        .styles
+        .iter()
+        .filter(|style| style.starts_with("STYLE") || style.starts_with("REGION"))
+    {
+        text.push_str(&format!("\n{style}\n"));
+    }
+
+    for cue in &document.cues {
+        text.push('\n');
+        if let Some(identifier) = &cue.identifier {
+            text.push_str(&format!("{identifier}\n"));
+        }
+        text.push_str(&format!(
+            "{} --> {}",
+            timestamp(cue.start),
+            timestamp(cue.end)
+        ));
+        if let Some(settings) = &cue.settings {
+            text.push_str(&format!(" {settings}"));
+        }
+        // Blank lines are not allowed inside a cue
+        let body = cue
+            .text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        text.push_str(&format!("\n{body}\n"));
+    }
+    text
+}
+
+/// Formats a WebVTT timestamp (`HH:MM:SS.mmm`).
+fn timestamp(duration: std::time::Duration) -> String {
+    let (hours, minutes, seconds, millis) = clock(duration);
+    format!("{hours:02}:{minutes:02}:{seconds:02}.{millis:03}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{parse, write};
+    use crate::core::format::{SubFormat, SubtitleDocument};
+
+    const VTT: &str = "WEBVTT\nKind: captions\n\nSTYLE\n::cue { color: yellow }\n\nNOTE a comment\n\nintro\n00:01.000 --> 00:02.000 align:start\n<v Mia>Hello\n\n00:00:03.000 --> 00:00:04.000\nBye\n";
+
+    #[test]
+    fn test_parse() {
+        let (cues, metadata) = parse(VTT);
+
+        assert_eq!(
+            metadata.headers,
+            vec![("Kind".to_string(), "captions".to_string())]
+        );
+        assert_eq!(metadata.styles, vec!["STYLE\n::cue { color: yellow }"]);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].identifier.as_deref(), Some("intro"));
+        assert_eq!(cues[0].settings.as_deref(), Some("align:start"));
+        assert_eq!(cues[0].start, Duration::from_secs(1));
+        assert_eq!(cues[1].text, "Bye");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let document = SubtitleDocument::parse(VTT, SubFormat::Vtt).unwrap();
+        let written = write(&document);
+        assert_eq!(
+            SubtitleDocument::parse(&written, SubFormat::Vtt).unwrap(),
+            document
+        );
+    }
+}
@@ -1,4 +1,10 @@
+#[cfg(feature = "encoding")]
+pub mod encoding;
+pub mod format;
 pub mod model;
+pub mod moviehash;
+pub mod release;
 mod response;
 
-pub use response::{Page, Response};
+pub(crate) use response::check_failure;
+pub use response::{Page, ParseMode, ParseWarning, Parsed, Response};
@@ -1,7 +1,15 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
 use crate::client::Filter;
 
+/// Offset segment of a subtitle search URL, replaced by [`Subtitle::page_url`] on every page.
+static OFFSET: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/offset=\d+").expect("valid regex"));
+
 /// Represents a subtitle entry with metadata and download information.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Subtitle {
     /// Unique identifier for the subtitle.
     pub id: u64,
@@ -13,8 +21,13 @@ pub struct Subtitle {
     pub language: String,
     /// CD or disc information (e.g., "CD1", "CD2").
     pub cd: String,
-    /// Upload date or timestamp.
+    /// Upload date in the `DD/MM/YY` format shown by the site.
     pub uploaded: String,
+    /// Full upload timestamp as given by the site (e.g. `"09/08/2023 21:14:33"`).
+    pub uploaded_raw: String,
+    /// Upload timestamp parsed from [`uploaded_raw`](Self::uploaded_raw).
+    #[cfg(feature = "chrono")]
+    pub uploaded_at: Option<chrono::NaiveDateTime>,
     /// Number of times the subtitle has been downloaded.
     pub downloads: u32,
     /// User rating for the subtitle.
@@ -23,6 +36,81 @@ pub struct Subtitle {
     pub uploader: Option<String>,
     /// Direct download link for the subtitle file.
     pub download_link: String,
+    /// File format of the subtitle (e.g. `srt`), if shown.
+    pub format: Option<SubFormat>,
+    /// Frames per second the subtitle was timed for, if shown.
+    pub fps: Option<f32>,
+    /// Whether the subtitle is for the hearing impaired.
+    pub hearing_impaired: bool,
+    /// Whether the subtitle is for a high definition release.
+    pub hd: bool,
+    /// Whether the subtitle comes from a trusted source.
+    pub trusted: bool,
+    /// Whether the subtitle was machine translated.
+    pub machine_translated: bool,
+}
+
+/// File format of a subtitle as shown in the result rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum SubFormat {
+    /// SubRip (`.srt`).
+    Srt,
+    /// MicroDVD (`.sub`).
+    Sub,
+    /// SubStation Alpha (`.ssa`).
+    Ssa,
+    /// Advanced SubStation Alpha (`.ass`).
+    Ass,
+    /// WebVTT (`.vtt`).
+    Vtt,
+    /// SAMI (`.smi`).
+    Smi,
+    /// MPL2 (`.mpl`).
+    Mpl,
+    /// TMPlayer (`.tmp`).
+    Tmp,
+    /// Plain text (`.txt`).
+    Txt,
+    /// Any other format, lowercased.
+    Other(String),
+}
+
+impl SubFormat {
+    /// Returns the format name as used by the site (e.g. `"srt"`).
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Srt => "srt",
+            Self::Sub => "sub",
+            Self::Ssa => "ssa",
+            Self::Ass => "ass",
+            Self::Vtt => "vtt",
+            Self::Smi => "smi",
+            Self::Mpl => "mpl",
+            Self::Tmp => "tmp",
+            Self::Txt => "txt",
+            Self::Other(format) => format,
+        }
+    }
+}
+
+impl From<&str> for SubFormat {
+    /// Creates a `SubFormat` from a format name or file extension (case-insensitive).
+    fn from(value: &str) -> Self {
+        match value.trim().trim_start_matches('.').to_lowercase().as_str() {
+            "srt" => Self::Srt,
+            "sub" => Self::Sub,
+            "ssa" => Self::Ssa,
+            "ass" => Self::Ass,
+            "vtt" => Self::Vtt,
+            "smi" => Self::Smi,
+            "mpl" => Self::Mpl,
+            "tmp" => Self::Tmp,
+            "txt" => Self::Txt,
+            other => Self::Other(other.to_string()),
+        }
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -63,14 +151,43 @@ impl Subtitle {
             rating,
             uploader,
             download_link: format!("https://dl.opensubtitles.org/en/download/sub/{id}"),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the full upload timestamp, also parsing it with the `chrono` feature.
+    #[cfg(test)]
+    pub(crate) fn with_uploaded_raw(mut self, uploaded_raw: String) -> Self {
+        #[cfg(feature = "chrono")]
+        {
+            self.uploaded_at = Self::parse_uploaded(&uploaded_raw);
         }
+        self.uploaded_raw = uploaded_raw;
+        self
+    }
+
+    /// Parses an upload timestamp as found in the result rows: `DD/MM/YYYY HH:MM:SS` (cell title),
+    /// `YYYY-MM-DDTHH:MM:SS` (`<time datetime>`) or `DD/MM/YY` (cell text, at midnight).
+    #[cfg(feature = "chrono")]
+    pub(crate) fn parse_uploaded(raw: &str) -> Option<chrono::NaiveDateTime> {
+        use chrono::{NaiveDate, NaiveDateTime};
+
+        let raw = raw.trim();
+        NaiveDateTime::parse_from_str(raw, "%d/%m/%Y %H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S"))
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(raw, "%d/%m/%y")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
     }
 
     /// Checks if a given URL refers to a subtitle resource.
     ///
-    /// Returns `true` if the URL contains `"imdbid"` or `"idmovie"`.
+    /// Returns `true` if the URL contains `"imdbid"`, `"idmovie"` or `"moviehash"`.
     pub(crate) fn is_subtitle(url: &str) -> bool {
-        url.contains("imdbid") || url.contains("idmovie")
+        url.contains("imdbid") || url.contains("idmovie") || url.contains("moviehash")
     }
 
     /// Modifies the given URL by appending filter parameters if it is a subtitle URL.
@@ -84,13 +201,27 @@ impl Subtitle {
             let sort = filter.and_then(|f| f.sort()).unwrap_or_default();
 
             url.push_str(&offset);
-            url.push_str(sort);
+            url.push_str(&sort);
+        }
+    }
+
+    /// Returns the given subtitle search URL pointing to the page starting at `offset`.
+    ///
+    /// Any existing `/offset=N` segment is replaced, and the new one is placed before the sort segment.
+    pub(crate) fn page_url(url: &str, offset: u32) -> String {
+        let url = OFFSET.replace(url, "");
+        let offset = format!("/offset={offset}");
+
+        match url.find("/sort-") {
+            Some(index) => format!("{}{offset}{}", &url[..index], &url[index..]),
+            None => format!("{url}{offset}"),
         }
     }
 }
 
 /// Represents a movie with an associated subtitles search link.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Movie {
     /// Unique identifier for the movie.
     pub id: u64,
@@ -101,21 +232,231 @@ pub struct Movie {
 }
 
 impl Movie {
+    /// Returns the release year shown at the end of the name (e.g. `"The Godfather (1972)"`).
+    pub fn year(&self) -> Option<u32> {
+        let (_, year) = self.name.trim_end().strip_suffix(')')?.rsplit_once('(')?;
+        year.parse().ok()
+    }
+
     /// Creates a new `Movie` instance with a generated subtitles search link.
     ///
     /// # Arguments
+    /// * `base_url` - Base URL of the site.
     /// * `id` - Movie ID.
     /// * `name` - Movie title.
     /// * `languages` - Language filter for subtitles (e.g., "eng").
     /// * `offset` - Pagination offset.
     /// * `sort` - Sort parameter.
-    pub(crate) fn new(id: u64, name: String, languages: &str, offset: &str, sort: &str) -> Self {
+    pub(crate) fn new(
+        base_url: &str,
+        id: u64,
+        name: String,
+        languages: &str,
+        offset: &str,
+        sort: &str,
+    ) -> Self {
         Self {
             id,
             name,
             subtitles_link: format!(
-                "https://www.opensubtitles.org/en/search/sublanguageid-{languages}/idmovie-{id}{offset}{sort}"
+                "{base_url}/en/search/sublanguageid-{languages}/idmovie-{id}{offset}{sort}"
             ),
         }
     }
 }
+
+/// Represents an episode of a TV series with an associated subtitles search link.
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Episode {
+    /// IMDb identifier of the episode.
+    pub id: u64,
+    /// Season number.
+    pub season: u32,
+    /// Episode number within the season.
+    pub episode: u32,
+    /// Episode title.
+    pub name: String,
+    /// URL to search for subtitles for this episode.
+    pub subtitles_link: String,
+}
+
+impl Episode {
+    /// Creates a new `Episode` instance with a generated subtitles search link.
+    ///
+    /// # Arguments
+    /// * `base_url` - Base URL of the site.
+    /// * `id` - IMDb ID of the episode.
+    /// * `season` - Season number.
+    /// * `episode` - Episode number.
+    /// * `name` - Episode title.
+    /// * `languages` - Language filter for subtitles (e.g., "eng").
+    /// * `offset` - Pagination offset.
+    /// * `sort` - Sort parameter.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        base_url: &str,
+        id: u64,
+        season: u32,
+        episode: u32,
+        name: String,
+        languages: &str,
+        offset: &str,
+        sort: &str,
+    ) -> Self {
+        Self {
+            id,
+            season,
+            episode,
+            name,
+            subtitles_link: format!(
+                "{base_url}/en/search/sublanguageid-{languages}/imdbid-{id}{offset}{sort}"
+            ),
+        }
+    }
+}
+
+/// Represents a single file extracted from a downloaded subtitle archive.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SubtitleFile {
+    /// File name of the entry, without its directories.
+    pub name: String,
+    /// Raw content of the file.
+    pub bytes: Vec<u8>,
+}
+
+impl SubtitleFile {
+    /// Extracts the subtitle files contained in a ZIP archive downloaded from opensubtitles.org.
+    ///
+    /// Directories and the `.nfo` files bundled by the site are skipped, as are entries whose
+    /// path escapes the archive (e.g. `../x` or `/etc/x`). Only the file name of each entry is
+    /// kept, so it is safe to join with a target directory.
+    ///
+    /// # Arguments
+    /// * `archive` - Raw bytes of the ZIP archive.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes are not a valid ZIP archive or an entry cannot be read.
+    pub fn extract(archive: &[u8]) -> crate::Result<Vec<Self>> {
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive))?;
+        let mut files = Vec::new();
+
+        for index in 0..zip.len() {
+            let mut entry = zip.by_index(index)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let Some(name) = entry.enclosed_name().and_then(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            }) else {
+                continue;
+            };
+            if name.to_lowercase().ends_with(".nfo") {
+                continue;
+            }
+
+            // The size in the header is not trusted for the allocation
+            let mut bytes = Vec::new();
+            std::io::Read::read_to_end(&mut entry, &mut bytes)?;
+            files.push(Self { name, bytes });
+        }
+
+        Ok(files)
+    }
+
+    /// Detects the character encoding of the file and transcodes it to UTF-8, using the
+    /// language of `subtitle` as hint. See [`encoding::decode`](crate::encoding::decode).
+    #[cfg(feature = "encoding")]
+    pub fn decode(&self, subtitle: &Subtitle) -> crate::encoding::Decoded {
+        let language = crate::Language::try_from(subtitle.language.as_str()).ok();
+        crate::encoding::decode(&self.bytes, language)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::{SubFormat, Subtitle, SubtitleFile};
+
+    #[test]
+    fn test_extract_skips_nfo() {
+        let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+
+        archive.start_file("movie.srt", options).unwrap();
+        archive
+            .write_all(b"1\n00:00:01,000 --> 00:00:02,000\nHello\n")
+            .unwrap();
+        archive.start_file("opensubtitles.nfo", options).unwrap();
+        archive.write_all(b"info").unwrap();
+        let bytes = archive.finish().unwrap().into_inner();
+
+        let files = SubtitleFile::extract(&bytes).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name, "movie.srt");
+        assert!(files[0].bytes.starts_with(b"1\n00:00:01,000"));
+    }
+
+    #[test]
+    fn test_extract_sanitizes_names() {
+        let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+
+        for name in ["../../escape.srt", "/etc/absolute.srt", "subs/nested.srt"] {
+            archive.start_file(name, options).unwrap();
+            archive.write_all(b"1").unwrap();
+        }
+        let bytes = archive.finish().unwrap().into_inner();
+
+        let files = SubtitleFile::extract(&bytes).unwrap();
+        let names = files
+            .iter()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["nested.srt"]);
+    }
+
+    #[test]
+    fn test_page_url() {
+        assert_eq!(
+            Subtitle::page_url("https://host/en/search/idmovie-1/sort-7/asc-0", 40),
+            "https://host/en/search/idmovie-1/offset=40/sort-7/asc-0"
+        );
+        assert_eq!(
+            Subtitle::page_url("https://host/en/search/idmovie-1/offset=40", 80),
+            "https://host/en/search/idmovie-1/offset=80"
+        );
+    }
+
+    #[test]
+    fn test_extract_invalid_archive() {
+        assert!(SubtitleFile::extract(b"<html>not a zip</html>").is_err());
+    }
+
+    #[test]
+    fn test_sub_format_from_str() {
+        assert_eq!(SubFormat::from("SRT"), SubFormat::Srt);
+        assert_eq!(SubFormat::from(".ass"), SubFormat::Ass);
+        assert_eq!(SubFormat::from("pjs"), SubFormat::Other("pjs".to_string()));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_parse_uploaded() {
+        use chrono::{NaiveDate, NaiveDateTime};
+
+        let expected = |h, m, s| {
+            NaiveDate::from_ymd_opt(2023, 8, 9)
+                .and_then(|date| date.and_hms_opt(h, m, s))
+                .unwrap()
+        };
+        let parse = |raw| Subtitle::parse_uploaded(raw);
+
+        assert_eq!(parse("09/08/2023 21:14:33"), Some(expected(21, 14, 33)));
+        assert_eq!(parse("2023-08-09T21:14:33"), Some(expected(21, 14, 33)));
+        assert_eq!(parse("09/08/23"), Some(expected(0, 0, 0)));
+        assert_eq!(parse("yesterday"), None::<NaiveDateTime>);
+    }
+}
@@ -0,0 +1,100 @@
+//! OpenSubtitles movie hash of local video files.
+//!
+//! The hash is the file size plus the sum of the 64-bit little-endian words of the first
+//! and the last 64 KiB of the file (wrapping on overflow). It is used with
+//! [`SearchBy::Hash`](crate::SearchBy::Hash) to find subtitles matching an exact release.
+//!
+//! # Example
+//! ```no_run
+//! use opensubs::{SearchBy, moviehash};
+//!
+//! let hash = moviehash::from_path("movie.mkv").expect("error");
+//! let search_by: SearchBy = hash.into();
+//! ```
+
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// Number of bytes hashed at the beginning and at the end of the file.
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Movie hash and size of a video file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovieHash {
+    /// 64-bit OpenSubtitles hash.
+    pub hash: u64,
+    /// File size in bytes.
+    pub size: u64,
+}
+
+impl std::fmt::Display for MovieHash {
+    /// Formats the hash as the 16 hexadecimal digits used by opensubtitles.org.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:016x}", self.hash)
+    }
+}
+
+/// Computes the [`MovieHash`] of the file at `path`.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or is smaller than 64 KiB.
+pub fn from_path(path: impl AsRef<Path>) -> crate::Result<MovieHash> {
+    compute(&mut File::open(path)?)
+}
+
+/// Computes the [`MovieHash`] of any seekable reader.
+///
+/// # Errors
+/// Returns an error if the reader fails or holds less than 64 KiB.
+pub fn compute<R: Read + Seek>(reader: &mut R) -> crate::Result<MovieHash> {
+    let size = reader.seek(SeekFrom::End(0))?;
+    if size < CHUNK_SIZE {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("file too small to compute movie hash ({size} bytes)"),
+        )
+        .into());
+    }
+
+    let mut hash = size;
+    for offset in [0, size - CHUNK_SIZE] {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut chunk = vec![0; CHUNK_SIZE as usize];
+        reader.read_exact(&mut chunk)?;
+
+        hash = chunk
+            .chunks_exact(8)
+            .map(|word| u64::from_le_bytes(word.try_into().expect("8 bytes word")))
+            .fold(hash, u64::wrapping_add);
+    }
+
+    Ok(MovieHash { hash, size })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{CHUNK_SIZE, MovieHash, compute};
+
+    #[test]
+    fn test_compute_zeros() {
+        let size = CHUNK_SIZE * 3;
+        let hash = compute(&mut Cursor::new(vec![0u8; size as usize])).unwrap();
+        assert_eq!(hash, MovieHash { hash: size, size });
+    }
+
+    #[test]
+    fn test_compute_words() {
+        let hash = compute(&mut Cursor::new(vec![1u8; CHUNK_SIZE as usize * 2])).unwrap();
+        assert_eq!(hash.to_string(), "4040404040424000");
+    }
+
+    #[test]
+    fn test_compute_too_small() {
+        assert!(compute(&mut Cursor::new(vec![0u8; 1024])).is_err());
+    }
+}
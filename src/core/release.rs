@@ -0,0 +1,558 @@
+//! Release names and ranking of subtitles against a local video file.
+//!
+//! [`Subtitle::name`] usually holds the release the subtitle was timed for (e.g.
+//! `Movie.2019.1080p.BluRay.x264-GROUP`). A subtitle of the same release as the video file is
+//! in sync with it, so [`rank`] orders the subtitles of a search by the similarity of their
+//! release to the file name, combined with their downloads, rating and hearing impaired flag.
+//!
+//! # Example
+//! ```
+//! use opensubs::{Subtitle, release::{self, RankOptions}};
+//!
+//! let subtitles = vec![
+//!     Subtitle {
+//!         name: Some("Movie.2019.720p.WEB-DL.x264-OTHER".to_string()),
+//!         downloads: 5000,
+//!         ..Default::default()
+//!     },
+//!     Subtitle {
+//!         name: Some("Movie.2019.1080p.BluRay.x264-GROUP".to_string()),
+//!         downloads: 100,
+//!         ..Default::default()
+//!     },
+//! ];
+//!
+//! let ranked = release::rank(subtitles, "Movie.2019.1080p.BluRay.x264-GROUP.mkv", &RankOptions::default());
+//! assert_eq!(ranked[0].subtitle.downloads, 100);
+//! ```
+
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+
+use super::model::Subtitle;
+
+/// File extensions removed before parsing a release name.
+const EXTENSIONS: [&str; 16] = [
+    "mkv", "mp4", "avi", "m4v", "mov", "wmv", "mpg", "mpeg", "webm", "srt", "sub", "ssa", "ass",
+    "vtt", "zip", "nfo",
+];
+
+/// Weights of the fields of a release in [`Release::similarity`].
+const GROUP_WEIGHT: f32 = 0.3;
+const SOURCE_WEIGHT: f32 = 0.2;
+const TITLE_WEIGHT: f32 = 0.2;
+const RESOLUTION_WEIGHT: f32 = 0.1;
+const YEAR_WEIGHT: f32 = 0.1;
+const CODEC_WEIGHT: f32 = 0.1;
+
+/// Regexes of [`Release::parse`], compiled once for all the names ranked.
+static PATTERNS: LazyLock<Patterns> = LazyLock::new(Patterns::new);
+
+/// Compiled regexes of the parts of a release name.
+struct Patterns {
+    group: Regex,
+    episode: Regex,
+    season: Regex,
+    resolution: Regex,
+    sources: Vec<(Regex, &'static str)>,
+    codecs: Vec<(Regex, &'static str)>,
+    tag: Regex,
+    year: Regex,
+}
+
+impl Patterns {
+    fn new() -> Self {
+        let normalized = |patterns: &[(&str, &'static str)]| {
+            patterns
+                .iter()
+                .map(|(pattern, value)| (Self::part(pattern), *value))
+                .collect()
+        };
+
+        Self {
+            group: Regex::new(r"-([A-Za-z0-9]+)(?:\[[^\]]*\])?$").expect("valid regex"),
+            episode: Self::part(r"s(\d{1,2})[ .]?e(\d{1,3})|(\d{1,2})x(\d{2,3})"),
+            season: Self::part(r"s(\d{1,2})|season[ .]?(\d{1,2})"),
+            resolution: Self::part(r"(2160|1440|1080|720|576|480)[pi]|(4k|uhd)"),
+            sources: normalized(&[
+                (r"blu-?ray|bdrip|brrip|bdremux|bd25|bd50", "bluray"),
+                (r"web-?dl", "web-dl"),
+                (r"web-?rip", "webrip"),
+                (r"web", "web"),
+                (r"hdtv|pdtv", "hdtv"),
+                (r"dvd-?rip|dvd5|dvd9|dvdr|dvd", "dvd"),
+                (r"hdrip", "hdrip"),
+                (r"hdcam|cam", "cam"),
+                (r"hdts|telesync|ts", "telesync"),
+            ]),
+            codecs: normalized(&[
+                (r"x\.?264|h\.?264|avc", "x264"),
+                (r"x\.?265|h\.?265|hevc", "x265"),
+                (r"xvid", "xvid"),
+                (r"divx", "divx"),
+                (r"av1", "av1"),
+            ]),
+            tag: Self::part(
+                r"proper|repack|extended|unrated|remastered|limited|internal|multi|dubbed|subbed|complete|remux|hdr|10bit",
+            ),
+            year: Regex::new(r"[ .\-\[\(]((?:19|20)\d{2})(?:$|[ .\-\]\)])").expect("valid regex"),
+        }
+    }
+
+    /// Returns whether the lowercase `token` is a whole source or codec (e.g. `web-dl`).
+    fn is_known(&self, token: &str) -> bool {
+        self.sources.iter().chain(&self.codecs).any(|(regex, _)| {
+            regex
+                .find(token)
+                .is_some_and(|found| found.range() == (0..token.len()))
+        })
+    }
+
+    /// Compiles `pattern` to match a whole part of a lowercase name, between separators.
+    fn part(pattern: &str) -> Regex {
+        Regex::new(&format!(r"(?:^|[ .\-\[\(])(?:{pattern})(?:$|[ .\-\]\)])")).expect("valid regex")
+    }
+}
+
+/// Parts of a scene or P2P release name.
+///
+/// Source and codec are normalized (e.g. `BDRip` and `Blu-ray` are both `"bluray"`, `H.264` and
+/// `AVC` are both `"x264"`) so that equivalent releases compare equal.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Release {
+    /// Title, with separators replaced by spaces (e.g. `"The Matrix"`).
+    pub title: String,
+    /// Release year.
+    pub year: Option<u32>,
+    /// Vertical resolution (e.g. `"1080p"`, `"2160p"`).
+    pub resolution: Option<String>,
+    /// Normalized source: `"bluray"`, `"web-dl"`, `"webrip"`, `"web"`, `"hdtv"`, `"dvd"`,
+    /// `"hdrip"`, `"cam"` or `"telesync"`.
+    pub source: Option<String>,
+    /// Normalized video codec: `"x264"`, `"x265"`, `"xvid"`, `"divx"` or `"av1"`.
+    pub codec: Option<String>,
+    /// Release group (e.g. `"GROUP"`).
+    pub group: Option<String>,
+    /// Season of a TV episode.
+    pub season: Option<u32>,
+    /// Episode number of a TV episode.
+    pub episode: Option<u32>,
+}
+
+impl Release {
+    /// Parses a release or file name (e.g. `/videos/Show.S01E02.720p.HDTV.x264-GROUP.mkv`).
+    ///
+    /// Directories and video or subtitle extensions are ignored. Parts that cannot be found
+    /// are left empty; the title is whatever precedes the first recognized part.
+    pub fn parse(name: &str) -> Self {
+        let name = name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+        let name = match name.rsplit_once('.') {
+            Some((stem, extension)) if EXTENSIONS.contains(&extension.to_lowercase().as_str()) => {
+                stem
+            }
+            _ => name,
+        };
+        let name = name.replace('_', ".");
+
+        let patterns = &*PATTERNS;
+        // A trailing hyphenated source or codec (e.g. "WEB-DL", "Blu-ray") is not a group
+        let group = patterns.group.captures(&name).and_then(|captures| {
+            let start = captures.get(0).expect("whole match").start();
+            let group = captures.get(1).expect("group");
+            let token_start = name[..start]
+                .rfind([' ', '.', '[', '('])
+                .map_or(0, |index| index + 1);
+            let token = name[token_start..group.end()].to_ascii_lowercase();
+            (!patterns.is_known(&token)).then(|| (start, group.as_str().to_string()))
+        });
+        let (name, group) = match group {
+            Some((start, group)) => (name[..start].to_string(), Some(group)),
+            None => (name, None),
+        };
+        // ASCII lowercase keeps the byte offsets of `name`
+        let lower = name.to_ascii_lowercase();
+
+        let find = |regex: &Regex| {
+            regex
+                .captures(&lower)
+                .map(|captures| (captures.get(0).expect("whole match").start(), captures))
+        };
+        let normalized = |regexes: &[(Regex, &'static str)]| {
+            regexes
+                .iter()
+                .filter_map(|(regex, value)| find(regex).map(|(start, _)| (start, *value)))
+                .min_by_key(|(start, _)| *start)
+        };
+
+        let episode = find(&patterns.episode);
+        let season = find(&patterns.season);
+        let resolution = find(&patterns.resolution);
+        let source = normalized(&patterns.sources);
+        let codec = normalized(&patterns.codecs);
+        let tag = find(&patterns.tag);
+
+        // The year closest to the other parts wins, so that "Blade.Runner.2049.2017" keeps
+        // its title
+        let markers = [
+            episode.as_ref().map(|(start, _)| *start),
+            season.as_ref().map(|(start, _)| *start),
+            resolution.as_ref().map(|(start, _)| *start),
+            source.map(|(start, _)| start),
+            codec.map(|(start, _)| start),
+            tag.as_ref().map(|(start, _)| *start),
+        ];
+        let first_marker = markers.into_iter().flatten().min().unwrap_or(lower.len());
+        let year = patterns
+            .year
+            .captures_iter(&lower)
+            .filter(|captures| captures.get(0).expect("whole match").start() < first_marker)
+            .last()
+            .map(|captures| {
+                (
+                    captures.get(0).expect("whole match").start(),
+                    captures[1].parse::<u32>().ok(),
+                )
+            });
+
+        let title_end = year.map_or(first_marker, |(start, _)| start.min(first_marker));
+        let title = name[..title_end]
+            .split([' ', '.', '-', '[', ']', '(', ')'])
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let number = |captures: &Captures, indexes: &[usize]| {
+            indexes
+                .iter()
+                .find_map(|index| captures.get(*index))
+                .and_then(|value| value.as_str().parse().ok())
+        };
+
+        Self {
+            title,
+            year: year.and_then(|(_, year)| year),
+            resolution: resolution.map(|(_, captures)| match captures.get(1) {
+                Some(height) => format!("{}p", height.as_str()),
+                None => "2160p".to_string(),
+            }),
+            source: source.map(|(_, source)| source.to_string()),
+            codec: codec.map(|(_, codec)| codec.to_string()),
+            group,
+            season: episode
+                .as_ref()
+                .and_then(|(_, captures)| number(captures, &[1, 3]))
+                .or_else(|| season.and_then(|(_, captures)| number(&captures, &[1, 2]))),
+            episode: episode.and_then(|(_, captures)| number(&captures, &[2, 4])),
+        }
+    }
+
+    /// Returns how similar `other` is to this release, from `0.0` to `1.0`.
+    ///
+    /// Only the parts known in this release are compared. The group and the source weigh the
+    /// most, as they decide the timing of a subtitle. A different season or episode gives
+    /// `0.0`.
+    pub fn similarity(&self, other: &Release) -> f32 {
+        let differs = |this: Option<u32>, other: Option<u32>| {
+            this.zip(other).is_some_and(|(this, other)| this != other)
+        };
+        if differs(self.season, other.season) || differs(self.episode, other.episode) {
+            return 0.0;
+        }
+
+        let mut total = 0.0;
+        let mut score = 0.0;
+        let mut compare = |weight: f32, similarity: Option<f32>| {
+            if let Some(similarity) = similarity {
+                total += weight;
+                score += weight * similarity;
+            }
+        };
+        let equal = |this: &Option<String>, other: &Option<String>| {
+            this.as_ref().map(|this| {
+                let equal = other
+                    .as_ref()
+                    .is_some_and(|other| this.eq_ignore_ascii_case(other));
+                if equal { 1.0 } else { 0.0 }
+            })
+        };
+
+        compare(
+            TITLE_WEIGHT,
+            (!self.title.is_empty()).then(|| word_similarity(&self.title, &other.title)),
+        );
+        compare(
+            YEAR_WEIGHT,
+            self.year
+                .map(|year| if other.year == Some(year) { 1.0 } else { 0.0 }),
+        );
+        compare(GROUP_WEIGHT, equal(&self.group, &other.group));
+        compare(SOURCE_WEIGHT, equal(&self.source, &other.source));
+        compare(
+            RESOLUTION_WEIGHT,
+            equal(&self.resolution, &other.resolution),
+        );
+        compare(CODEC_WEIGHT, equal(&self.codec, &other.codec));
+
+        if total == 0.0 { 0.0 } else { score / total }
+    }
+}
+
+/// Returns the Jaccard similarity of the lowercase words of `this` and `other`.
+fn word_similarity(this: &str, other: &str) -> f32 {
+    let words = |text: &str| {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_string)
+            .collect::<std::collections::HashSet<_>>()
+    };
+    let (this, other) = (words(this), words(other));
+    let union = this.union(&other).count();
+
+    if union == 0 {
+        0.0
+    } else {
+        this.intersection(&other).count() as f32 / union as f32
+    }
+}
+
+/// Weights and preferences of [`rank`].
+///
+/// # Example
+/// ```
+/// use opensubs::release::RankOptions;
+///
+/// // Prefer subtitles without hearing impaired annotations, and favour popularity
+/// let options = RankOptions::default()
+///     .hearing_impaired(false)
+///     .downloads_weight(0.4);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankOptions {
+    /// Weight of the release name similarity.
+    name_weight: f32,
+    /// Weight of the downloads, relative to the most downloaded candidate.
+    downloads_weight: f32,
+    /// Weight of the rating, out of 10.
+    rating_weight: f32,
+    /// Weight of matching the hearing impaired preference.
+    hearing_impaired_weight: f32,
+    /// Whether hearing impaired subtitles are preferred (`None` for no preference).
+    hearing_impaired: Option<bool>,
+}
+
+impl Default for RankOptions {
+    /// Creates options weighting the name similarity 0.6, the downloads 0.2, the rating 0.1
+    /// and the hearing impaired preference 0.1, without hearing impaired preference.
+    fn default() -> Self {
+        Self {
+            name_weight: 0.6,
+            downloads_weight: 0.2,
+            rating_weight: 0.1,
+            hearing_impaired_weight: 0.1,
+            hearing_impaired: None,
+        }
+    }
+}
+
+impl RankOptions {
+    /// Sets the weight of the release name similarity.
+    pub fn name_weight(mut self, weight: f32) -> Self {
+        self.name_weight = weight;
+        self
+    }
+
+    /// Sets the weight of the downloads.
+    pub fn downloads_weight(mut self, weight: f32) -> Self {
+        self.downloads_weight = weight;
+        self
+    }
+
+    /// Sets the weight of the rating.
+    pub fn rating_weight(mut self, weight: f32) -> Self {
+        self.rating_weight = weight;
+        self
+    }
+
+    /// Sets the weight of matching the hearing impaired preference.
+    pub fn hearing_impaired_weight(mut self, weight: f32) -> Self {
+        self.hearing_impaired_weight = weight;
+        self
+    }
+
+    /// Prefers hearing impaired subtitles if `true`, or subtitles without hearing impaired
+    /// annotations if `false`.
+    pub fn hearing_impaired(mut self, hearing_impaired: bool) -> Self {
+        self.hearing_impaired = Some(hearing_impaired);
+        self
+    }
+}
+
+/// A subtitle with its score against a video file.
+#[derive(Debug, PartialEq)]
+pub struct Ranked {
+    /// The ranked subtitle.
+    pub subtitle: Subtitle,
+    /// Release name similarity, from `0.0` to `1.0`. See [`Release::similarity`].
+    pub similarity: f32,
+    /// Weighted score the subtitles are ordered by.
+    pub score: f32,
+}
+
+/// Ranks `subtitles` against the video file `filename`, best match first.
+///
+/// The release of every subtitle is parsed from [`Subtitle::name`] (or [`Subtitle::movie`]
+/// when missing) and compared with the release of the file. The score adds the weighted
+/// similarity, downloads (on a logarithmic scale relative to the most downloaded candidate),
+/// rating and hearing impaired preference of [`RankOptions`]. Ties keep the original order.
+pub fn rank(subtitles: Vec<Subtitle>, filename: &str, options: &RankOptions) -> Vec<Ranked> {
+    let release = Release::parse(filename);
+    let max_downloads = subtitles
+        .iter()
+        .map(|subtitle| subtitle.downloads)
+        .max()
+        .unwrap_or_default();
+
+    let mut ranked = subtitles
+        .into_iter()
+        .map(|subtitle| {
+            let name = subtitle.name.as_deref().unwrap_or(&subtitle.movie);
+            let similarity = release.similarity(&Release::parse(name));
+
+            let downloads = match max_downloads {
+                0 => 0.0,
+                max => (subtitle.downloads as f32).ln_1p() / (max as f32).ln_1p(),
+            };
+            let rating = (subtitle.rating / 10.0).clamp(0.0, 1.0);
+            let hearing_impaired = match options.hearing_impaired {
+                Some(preferred) if preferred == subtitle.hearing_impaired => 1.0,
+                _ => 0.0,
+            };
+
+            let score = options.name_weight * similarity
+                + options.downloads_weight * downloads
+                + options.rating_weight * rating
+                + options.hearing_impaired_weight * hearing_impaired;
+            Ranked {
+                subtitle,
+                similarity,
+                score,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RankOptions, Release, rank};
+    use crate::Subtitle;
+
+    #[test]
+    fn test_parse_movie() {
+        assert_eq!(
+            Release::parse("/videos/The.Matrix.1999.1080p.BluRay.x264-GROUP[rarbg].mkv"),
+            Release {
+                title: "The Matrix".to_string(),
+                year: Some(1999),
+                resolution: Some("1080p".to_string()),
+                source: Some("bluray".to_string()),
+                codec: Some("x264".to_string()),
+                group: Some("GROUP".to_string()),
+                ..Default::default()
+            }
+        );
+
+        let release = Release::parse("Blade Runner 2049 (2017) 2160p WEB-DL H.265-OTHER");
+        assert_eq!(release.title, "Blade Runner 2049");
+        assert_eq!(release.year, Some(2017));
+        assert_eq!(release.resolution.as_deref(), Some("2160p"));
+        assert_eq!(release.source.as_deref(), Some("web-dl"));
+        assert_eq!(release.codec.as_deref(), Some("x265"));
+        assert_eq!(release.group.as_deref(), Some("OTHER"));
+
+        let release = Release::parse("2001.A.Space.Odyssey.1968.DVDRip.XviD");
+        assert_eq!(release.title, "2001 A Space Odyssey");
+        assert_eq!(release.year, Some(1968));
+        assert_eq!(release.source.as_deref(), Some("dvd"));
+        assert_eq!(release.codec.as_deref(), Some("xvid"));
+        assert_eq!(release.group, None);
+
+        assert_eq!(Release::parse("Pulp Fiction (1994)").title, "Pulp Fiction");
+
+        let release = Release::parse("Movie.2019.1080p.WEB-DL");
+        assert_eq!(release.title, "Movie");
+        assert_eq!(release.source.as_deref(), Some("web-dl"));
+        assert_eq!(release.group, None);
+
+        let release = Release::parse("Movie 2019 720p x264 Blu-ray.mkv");
+        assert_eq!(release.source.as_deref(), Some("bluray"));
+        assert_eq!(release.codec.as_deref(), Some("x264"));
+        assert_eq!(release.group, None);
+    }
+
+    #[test]
+    fn test_parse_episode() {
+        let release = Release::parse("Show_Name.S01E02.720p.HDTV.x264-GROUP.mkv");
+        assert_eq!(release.title, "Show Name");
+        assert_eq!((release.season, release.episode), (Some(1), Some(2)));
+        assert_eq!(release.source.as_deref(), Some("hdtv"));
+
+        let release = Release::parse("Show.Name.3x10.WEBRip");
+        assert_eq!((release.season, release.episode), (Some(3), Some(10)));
+        assert_eq!(release.source.as_deref(), Some("webrip"));
+
+        let release = Release::parse("Show Name Season 2 Complete");
+        assert_eq!(release.title, "Show Name");
+        assert_eq!((release.season, release.episode), (Some(2), None));
+    }
+
+    #[test]
+    fn test_similarity() {
+        let file = Release::parse("Movie.2019.1080p.BluRay.x264-GROUP.mkv");
+
+        assert_eq!(file.similarity(&file), 1.0);
+        let similarity = file.similarity(&Release::parse("Movie.2019.720p.BRRip.x264-GROUP"));
+        assert!((similarity - 0.9).abs() < 1e-6);
+        assert!(file.similarity(&Release::parse("Movie.2019.720p.WEB-DL.x265-OTHER")) < 0.5);
+
+        let episode = Release::parse("Show.S01E02.720p.HDTV.x264-GROUP");
+        assert_eq!(
+            episode.similarity(&Release::parse("Show.S01E03.720p.HDTV.x264-GROUP")),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_rank() {
+        let subtitle = |id, name: &str, downloads, rating, hearing_impaired| Subtitle {
+            id,
+            name: Some(name.to_string()),
+            downloads,
+            rating,
+            hearing_impaired,
+            ..Default::default()
+        };
+        let ids = |options: &RankOptions| {
+            let subtitles = vec![
+                subtitle(1, "Movie.2019.720p.WEB-DL.x265-OTHER", 10_000, 9.0, false),
+                subtitle(2, "Movie.2019.1080p.BluRay.x264-GROUP", 200, 0.0, true),
+                subtitle(3, "Movie.2019.1080p.BluRay.x264-GROUP", 200, 0.0, false),
+            ];
+            rank(subtitles, "Movie.2019.1080p.BluRay.x264-GROUP.mkv", options)
+                .iter()
+                .map(|ranked| ranked.subtitle.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(&RankOptions::default()), vec![2, 3, 1]);
+        assert_eq!(
+            ids(&RankOptions::default().hearing_impaired(false)),
+            vec![3, 2, 1]
+        );
+        assert_eq!(ids(&RankOptions::default().name_weight(0.0)), vec![1, 2, 3]);
+    }
+}
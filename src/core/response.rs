@@ -1,13 +1,17 @@
-use crate::{client::Filter, core::model::Subtitle};
+use crate::{
+    client::{Filter, origin},
+    core::model::Subtitle,
+};
 
 use super::model;
 use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 
 /// Represents pagination information for search results.
 ///
 /// The `Page` struct holds the range (`from` to `to`) and the total number of items.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Page {
     /// The starting index of the current page.
     pub from: u32,
@@ -48,13 +52,128 @@ impl From<Option<String>> for Page {
 
 /// Represents a parsed response from a search page.
 ///
-/// The response can either be a list of movies or a list of subtitles with pagination.
-#[derive(Debug)]
+/// The response can either be a list of movies, a list of subtitles with pagination
+/// or the list of episodes of a TV series. More kinds of pages may be supported in the
+/// future, so matches need a wildcard arm.
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "data", rename_all = "lowercase")
+)]
 pub enum Response {
     /// A list of movies found in the search results.
     Movie(Vec<model::Movie>),
     /// A paginated list of subtitles found in the search results.
     Subtitle(Page, Vec<model::Subtitle>),
+    /// The episodes of a TV series, grouped by season in listing order.
+    Episode(Vec<model::Episode>),
+}
+
+/// Controls how [`Response`] parsing reacts to fields that cannot be parsed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Unparseable fields fall back to defaults and a [`ParseWarning`] is recorded (default).
+    #[default]
+    Lenient,
+    /// The first unparseable field fails with [`Error::Parse`](crate::Error::Parse).
+    Strict,
+}
+
+/// A field that could not be parsed in lenient mode and fell back to its default value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// Name of the field (e.g. `"downloads"`).
+    pub field: &'static str,
+    /// Row index in the results table (the header is row 0).
+    pub row: usize,
+    /// Truncated HTML of the offending row or element.
+    pub snippet: String,
+}
+
+impl std::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "cannot parse {} at row {}: {}",
+            self.field, self.row, self.snippet
+        )
+    }
+}
+
+/// A parsed [`Response`] along with the warnings collected in lenient mode.
+#[derive(Debug, PartialEq)]
+pub struct Parsed {
+    /// The parsed response.
+    pub response: Response,
+    /// Fields that fell back to defaults. Always empty in strict mode.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Maximum number of characters kept in a parse error or warning snippet.
+const SNIPPET_LEN: usize = 160;
+
+/// Flags and FPS shown as icons in the first column of a subtitle row.
+#[derive(Debug, Default)]
+struct RowFlags {
+    fps: Option<f32>,
+    hearing_impaired: bool,
+    hd: bool,
+    trusted: bool,
+    machine_translated: bool,
+}
+
+/// Collects parse failures according to the [`ParseMode`].
+struct Diagnostics {
+    mode: ParseMode,
+    warnings: Vec<ParseWarning>,
+}
+
+impl Diagnostics {
+    /// Returns `value` if present. Otherwise fails in strict mode, or records a warning
+    /// and returns `None` in lenient mode.
+    fn check<T>(
+        &mut self,
+        value: Option<T>,
+        field: &'static str,
+        row: usize,
+        snippet: impl FnOnce() -> String,
+    ) -> crate::Result<Option<T>> {
+        if value.is_some() {
+            return Ok(value);
+        }
+
+        let snippet = snippet().chars().take(SNIPPET_LEN).collect::<String>();
+        match self.mode {
+            ParseMode::Strict => Err(crate::Error::Parse {
+                field,
+                row,
+                snippet,
+            }),
+            ParseMode::Lenient => {
+                self.warnings.push(ParseWarning {
+                    field,
+                    row,
+                    snippet,
+                });
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Lowercase titles of the pages served when too many requests are sent.
+const RATE_LIMITED_TITLES: [&str; 2] = ["too many requests", "429 too many requests"];
+
+/// Lowercase titles of the pages served while the site is in maintenance.
+const MAINTENANCE_TITLES: [&str; 3] = ["site in maintenance", "maintenance", "under maintenance"];
+
+/// Fails if `html` is a site failure page (captcha, maintenance or rate limiting).
+///
+/// Pages holding a results table are never considered failure pages.
+pub(crate) fn check_failure(html: &str) -> crate::Result {
+    Response::check_document(&Html::parse_document(html))
 }
 
 impl Response {
@@ -66,159 +185,784 @@ impl Response {
     /// * `url` - The URL of the search page.
     /// * `html` - The HTML content of the page.
     /// * `filter` - Optional filter to apply for language, offset, and sort.
+    /// * `mode` - Whether unparseable fields fail or are reported as warnings.
     ///
     /// # Returns
     /// * `Response::Movie` if the page contains a list of movies.
     /// * `Response::Subtitle` if the page contains a list of subtitles, along with pagination info.
+    /// * `Response::Episode` if the page is the season listing of a TV series.
     ///
     /// # Errors
-    /// Returns an error if HTML parsing or selector creation fails.
-    pub(crate) fn create(url: &str, html: &str, filter: Option<&Filter>) -> crate::Result<Self> {
+    /// Returns an error if HTML parsing or selector creation fails, if the page is a captcha,
+    /// maintenance or rate limiting page, or if a field cannot be parsed in [`ParseMode::Strict`].
+    pub(crate) fn parse(
+        url: &str,
+        html: &str,
+        filter: Option<&Filter>,
+        mode: ParseMode,
+    ) -> crate::Result<Parsed> {
         let document = Html::parse_document(html);
+        Self::check_document(&document)?;
+
+        let mut diagnostics = Diagnostics {
+            mode,
+            warnings: Vec::new(),
+        };
+
+        let response = if Subtitle::is_subtitle(url) {
+            if document
+                .select(&Selector::parse(r#"span[id^="season-"]"#)?)
+                .next()
+                .is_some()
+            {
+                Self::create_episodes(url, &document, filter, &mut diagnostics)?
+            } else {
+                Self::create_subtitles(&document, &mut diagnostics)?
+            }
+        } else {
+            Self::create_movies(url, &document, filter, &mut diagnostics)?
+        };
+
+        Ok(Parsed {
+            response,
+            warnings: diagnostics.warnings,
+        })
+    }
 
-        let table_selector = Selector::parse("table#search_results")?;
+    /// Fails if the document is a captcha, maintenance or "too many requests" page.
+    ///
+    /// Pages with results are never failures. Captchas are recognized by their widget and the
+    /// other pages by their exact title.
+    fn check_document(document: &Html) -> crate::Result {
+        if document
+            .select(&Selector::parse("table#search_results")?)
+            .next()
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let captcha = Selector::parse(
+            r#"div.g-recaptcha, div.h-captcha, form#captcha, input[name="captcha"], img[src*="captcha"]"#,
+        )?;
+        if document.select(&captcha).next().is_some() {
+            return Err(crate::Error::Captcha);
+        }
+
+        // Failure pages are standalone error pages, so their whole title is compared: result
+        // pages repeat the search in their title and headings (e.g. a movie called
+        // "Maintenance")
+        let title = document
+            .select(&Selector::parse("head > title")?)
+            .next()
+            .map(|title| {
+                title
+                    .text()
+                    .collect::<String>()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .to_lowercase()
+            })
+            .unwrap_or_default();
+        if RATE_LIMITED_TITLES.contains(&title.as_str()) {
+            return Err(crate::Error::RateLimited { retry_after: None });
+        }
+        if MAINTENANCE_TITLES.contains(&title.as_str()) {
+            return Err(crate::Error::Maintenance);
+        }
+
+        Ok(())
+    }
+
+    /// Parses a subtitles result page into a `Response::Subtitle`.
+    fn create_subtitles(document: &Html, diagnostics: &mut Diagnostics) -> crate::Result<Self> {
         let line_selector = Selector::parse("tr")?;
         let column_selector = Selector::parse("td")?;
+        let span_selector = Selector::parse("span")?;
+        let time_selector = Selector::parse("time")?;
+        let format_selector = Selector::parse("span.p")?;
+        let number_regex = Regex::new(r"\d+(\.\d+)?").expect("valid regex");
 
-        if Subtitle::is_subtitle(url) {
-            let page = match document.select(&Selector::parse("div#msg")?).next() {
-                Some(page) => page
-                    .select(&Selector::parse("span")?)
+        let page = match document.select(&Selector::parse("div#msg")?).next() {
+            Some(page) => {
+                let text = page
+                    .select(&span_selector)
                     .nth(1)
-                    .map(|page| page.text().collect::<Vec<_>>().join(" "))
-                    .into(),
-                None => Page::default(),
-            };
-
-            let mut subtitles = Vec::new();
-            if let Some(table) = document.select(&table_selector).next() {
-                // skip 1 (table header)
-                for line in table.select(&line_selector).skip(1) {
-                    let id = match line.attr("id") {
-                        // Omit non-display items
-                        Some(id) if !id.contains("ihtr") => id.strip_prefix("name").unwrap_or(id),
-                        _ => continue,
-                    }
-                    .parse()
+                    .map(|page| page.text().collect::<Vec<_>>().join(" "));
+                let parsed = Page::from(text.clone());
+                match text {
+                    Some(text) if parsed == Page::default() => diagnostics
+                        .check(None, "page", 0, || text)?
+                        .unwrap_or_default(),
+                    _ => parsed,
+                }
+            }
+            None => Page::default(),
+        };
+
+        let mut subtitles = Vec::new();
+        if let Some(table) = document
+            .select(&Selector::parse("table#search_results")?)
+            .next()
+        {
+            // skip 1 (table header)
+            for (row, line) in table.select(&line_selector).enumerate().skip(1) {
+                let id = match line.attr("id") {
+                    // Omit non-display items
+                    Some(id) if !id.contains("ihtr") => id.strip_prefix("name").unwrap_or(id),
+                    _ => continue,
+                }
+                .parse()
+                .ok();
+                let id = diagnostics
+                    .check(id, "id", row, || line.html())?
                     .unwrap_or_default();
 
-                    let movie_text = line
-                        .text()
-                        .take(2) // Omit links in movie name
-                        .filter(|text| !text.contains("Watch online"))
-                        .collect::<Vec<_>>();
+                let movie_text = line
+                    .text()
+                    .take(2) // Omit links in movie name
+                    .filter(|text| !text.contains("Watch online"))
+                    .collect::<Vec<_>>();
 
-                    let movie = movie_text
-                        .first()
-                        .map(|value| value.replace("\n", "").replace("\t", "").to_string())
-                        .unwrap_or_default();
+                let movie = movie_text
+                    .first()
+                    .map(|value| value.replace("\n", "").replace("\t", "").to_string())
+                    .filter(|movie| !movie.trim().is_empty());
+                let movie = diagnostics
+                    .check(movie, "movie", row, || line.html())?
+                    .unwrap_or_default();
 
-                    let name = movie_text
-                        .get(1)
-                        .map(|value| value.replace("\n", "").replace("\t", "").to_string());
+                let name = movie_text
+                    .get(1)
+                    .map(|value| value.replace("\n", "").replace("\t", "").to_string());
 
-                    // skip 1 (movie name and links)
-                    let mut data = line.select(&column_selector).skip(1);
-                    let language = data
-                        .next()
-                        .and_then(|column| {
-                            column
-                                .first_child()
-                                .and_then(|child| child.value().as_element())
-                                .and_then(|element| element.attr("title"))
-                        })
-                        .unwrap_or("Not Available")
-                        .to_string();
+                // skip 1 (movie name and links)
+                let mut data = line.select(&column_selector).skip(1);
+                let language = data.next().and_then(|column| {
+                    column
+                        .first_child()
+                        .and_then(|child| child.value().as_element())
+                        .and_then(|element| element.attr("title"))
+                        .map(str::to_string)
+                });
+                let language = diagnostics
+                    .check(language, "language", row, || line.html())?
+                    .unwrap_or("Not Available".to_string());
+
+                let cd = data
+                    .next()
+                    .map(|column| {
+                        column
+                            .text()
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                            .trim()
+                            .to_string()
+                    })
+                    .unwrap_or_default();
+
+                let uploaded_column = data.next();
+                let uploaded = uploaded_column
+                    .map(|column| {
+                        let mut date = column
+                            .text()
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                            .trim()
+                            .to_string();
+                        // Takes only the date format DD/MM/YY
+                        date.truncate(8);
+                        date
+                    })
+                    .filter(|date| !date.is_empty());
+                let uploaded = diagnostics
+                    .check(uploaded, "uploaded", row, || line.html())?
+                    .unwrap_or_default();
 
-                    let cd = data
-                        .next()
-                        .map(|column| {
+                // The full timestamp is in the cell title, or in the datetime of its <time> element
+                let uploaded_raw = uploaded_column
+                    .and_then(|column| {
+                        column.attr("title").map(str::to_string).or_else(|| {
                             column
-                                .text()
-                                .collect::<Vec<_>>()
-                                .join(" ")
-                                .trim()
-                                .to_string()
+                                .select(&time_selector)
+                                .next()
+                                .and_then(|time| time.attr("datetime"))
+                                .map(str::to_string)
                         })
-                        .unwrap_or_default();
+                    })
+                    .unwrap_or_else(|| uploaded.clone());
 
-                    let uploaded = data
-                        .next()
-                        .map(|column| {
-                            let mut date = column
-                                .text()
-                                .collect::<Vec<_>>()
-                                .join(" ")
-                                .trim()
-                                .to_string();
-                            // Takes only the date format DD/MM/YY
-                            date.truncate(8);
-                            date
-                        })
-                        .unwrap_or_default();
+                #[cfg(feature = "chrono")]
+                let uploaded_at = if uploaded_raw.is_empty() {
+                    None
+                } else {
+                    let uploaded_at = model::Subtitle::parse_uploaded(&uploaded_raw);
+                    diagnostics.check(uploaded_at, "uploaded_at", row, || line.html())?
+                };
 
-                    let downloads: u32 = data
-                        .next()
-                        .and_then(|column| column.text().next())
-                        .map(|text| text.trim().replace("x", ""))
-                        .and_then(|s| s.parse::<u32>().ok())
-                        .unwrap_or_default();
+                let downloads_column = data.next();
+                let downloads = downloads_column
+                    .and_then(|column| column.text().next())
+                    .map(|text| text.trim().replace("x", ""))
+                    .and_then(|s| s.parse::<u32>().ok());
+                let downloads = diagnostics
+                    .check(downloads, "downloads", row, || line.html())?
+                    .unwrap_or_default();
 
-                    let rating: f32 = data
-                        .next()
-                        .and_then(|column| column.select(&Selector::parse("span").unwrap()).next())
-                        .and_then(|column| column.text().next())
-                        .and_then(|text| text.parse::<f32>().ok())
-                        .unwrap_or_default();
+                // The format is shown below the downloads count
+                let format = downloads_column
+                    .and_then(|column| column.select(&format_selector).next())
+                    .map(|span| span.text().collect::<String>())
+                    .filter(|format| !format.trim().is_empty())
+                    .map(|format| model::SubFormat::from(format.as_str()));
 
-                    let uploader = data.nth(2).and_then(|column| {
-                        let name = column
+                // A rating cell without span means the subtitle has not been rated yet
+                let rating = match data
+                    .next()
+                    .and_then(|column| column.select(&span_selector).next())
+                {
+                    Some(span) => {
+                        let rating = span
                             .text()
-                            .collect::<Vec<_>>()
-                            .join(" ")
-                            .trim()
-                            .to_string();
-                        if name.is_empty() { None } else { Some(name) }
-                    });
+                            .next()
+                            .and_then(|text| text.trim().parse::<f32>().ok());
+                        diagnostics
+                            .check(rating, "rating", row, || span.html())?
+                            .unwrap_or_default()
+                    }
+                    None => 0.0,
+                };
+
+                let uploader = data.nth(2).and_then(|column| {
+                    let name = column
+                        .text()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .trim()
+                        .to_string();
+                    if name.is_empty() { None } else { Some(name) }
+                });
 
-                    subtitles.push(model::Subtitle::new(
+                let flags = line
+                    .select(&column_selector)
+                    .next()
+                    .map(|column| Self::row_flags(column, &number_regex))
+                    .unwrap_or_default();
+
+                subtitles.push(model::Subtitle {
+                    format,
+                    fps: flags.fps,
+                    hearing_impaired: flags.hearing_impaired,
+                    hd: flags.hd,
+                    trusted: flags.trusted,
+                    machine_translated: flags.machine_translated,
+                    uploaded_raw,
+                    #[cfg(feature = "chrono")]
+                    uploaded_at,
+                    ..model::Subtitle::new(
                         id, movie, name, language, cd, uploaded, downloads, rating, uploader,
-                    ));
-                }
+                    )
+                });
             }
-            Ok(Response::Subtitle(page, subtitles))
-        } else {
-            let mut movies = Vec::new();
-            if let Some(table) = document.select(&table_selector).next() {
-                let languages = filter
-                    .map(|f| f.languages_to_str())
-                    .unwrap_or("all".to_string());
-
-                let offset = filter.and_then(|f| f.offset()).unwrap_or_default();
-                let sort = filter.and_then(|f| f.sort()).unwrap_or_default();
-
-                // skip 1 (table header)
-                for line in table.select(&line_selector).skip(1) {
-                    let id: u64 = match line.attr("id") {
-                        Some(id) => id.strip_prefix("name").unwrap_or(id),
-                        _ => continue,
-                    }
-                    .parse()
+        }
+        Ok(Response::Subtitle(page, subtitles))
+    }
+
+    /// Extracts the icons and the FPS shown in the first column of a subtitle row.
+    ///
+    /// Icons are recognized by their `title` or their image file name.
+    fn row_flags(column: ElementRef, number_regex: &Regex) -> RowFlags {
+        let mut flags = RowFlags::default();
+
+        for element in column.descendants().filter_map(ElementRef::wrap) {
+            let title = element.attr("title").unwrap_or_default().to_lowercase();
+            let src = element.attr("src").unwrap_or_default().to_lowercase();
+            let file = src.rsplit('/').next().unwrap_or_default();
+
+            if title.contains("fps") || title.contains("frames per second") {
+                let text = element.text().collect::<String>();
+                flags.fps = number_regex
+                    .find(&text)
+                    .or_else(|| number_regex.find(&title))
+                    .and_then(|fps| fps.as_str().parse().ok());
+            }
+            if element.value().name() != "img" {
+                continue;
+            }
+
+            flags.hearing_impaired |=
+                title.contains("hearing impaired") || file.starts_with("hearing_impaired");
+            flags.hd |= title.contains("high definition") || file.starts_with("hd.");
+            flags.trusted |= title.contains("trusted") || file.starts_with("from_trusted");
+            flags.machine_translated |=
+                title.contains("machine translat") || file.starts_with("machine_translat");
+        }
+        flags
+    }
+
+    /// Parses a movies result page into a `Response::Movie`.
+    fn create_movies(
+        url: &str,
+        document: &Html,
+        filter: Option<&Filter>,
+        diagnostics: &mut Diagnostics,
+    ) -> crate::Result<Self> {
+        let mut movies = Vec::new();
+        if let Some(table) = document
+            .select(&Selector::parse("table#search_results")?)
+            .next()
+        {
+            let languages = filter
+                .map(|f| f.languages_to_str())
+                .unwrap_or("all".to_string());
+
+            let offset = filter.and_then(|f| f.offset()).unwrap_or_default();
+            let sort = filter.and_then(|f| f.sort()).unwrap_or_default();
+            let base_url = origin(url);
+
+            // skip 1 (table header)
+            for (row, line) in table.select(&Selector::parse("tr")?).enumerate().skip(1) {
+                let id = match line.attr("id") {
+                    Some(id) => id.strip_prefix("name").unwrap_or(id),
+                    _ => continue,
+                }
+                .parse()
+                .ok();
+                let id: u64 = diagnostics
+                    .check(id, "id", row, || line.html())?
                     .unwrap_or_default();
 
-                    let name = line
-                        .text()
-                        .take(2) // Omit links in movie name
-                        .filter(|text| !text.contains("Watch online"))
-                        .collect::<Vec<_>>()
-                        .first()
-                        .map(|value| value.replace("\n", "").replace("\t", "").to_string())
-                        .unwrap_or_default();
+                let name = line
+                    .text()
+                    .take(2) // Omit links in movie name
+                    .filter(|text| !text.contains("Watch online"))
+                    .collect::<Vec<_>>()
+                    .first()
+                    .map(|value| value.replace("\n", "").replace("\t", "").to_string())
+                    .filter(|name| !name.trim().is_empty());
+                let name = diagnostics
+                    .check(name, "name", row, || line.html())?
+                    .unwrap_or_default();
 
-                    movies.push(model::Movie::new(id, name, &languages, &offset, sort));
+                movies.push(model::Movie::new(
+                    &base_url, id, name, &languages, &offset, &sort,
+                ));
+            }
+        }
+
+        // Ranges are not fully supported by the site; movies without year are kept
+        if let Some(year) = filter.and_then(|f| f.year()) {
+            movies.retain(|movie| movie.year().is_none_or(|y| year.matches(y)));
+        }
+        Ok(Response::Movie(movies))
+    }
+
+    /// Parses the season listing page of a TV series into a `Response::Episode`.
+    ///
+    /// Season header rows contain a `span#season-N` and episode rows an `episodeNumber`
+    /// and a link to the `imdbid` subtitles search. Episodes without link are skipped.
+    fn create_episodes(
+        url: &str,
+        document: &Html,
+        filter: Option<&Filter>,
+        diagnostics: &mut Diagnostics,
+    ) -> crate::Result<Self> {
+        let season_selector = Selector::parse(r#"span[id^="season-"]"#)?;
+        let number_selector = Selector::parse(r#"[itemprop="episodeNumber"]"#)?;
+        let link_selector = Selector::parse(r#"a[href*="imdbid-"]"#)?;
+        let name_selector = Selector::parse(r#"[itemprop="name"]"#)?;
+        let id_regex = Regex::new(r"imdbid-(\d+)").expect("Error setting regex");
+
+        let languages = filter
+            .map(|f| f.languages_to_str())
+            .filter(|languages| !languages.is_empty())
+            .unwrap_or("all".to_string());
+        let offset = filter.and_then(|f| f.offset()).unwrap_or_default();
+        let sort = filter.and_then(|f| f.sort()).unwrap_or_default();
+        let base_url = origin(url);
+
+        let mut episodes = Vec::new();
+        let mut season = 0;
+        if let Some(table) = document
+            .select(&Selector::parse("table#search_results")?)
+            .next()
+        {
+            for (row, line) in table.select(&Selector::parse("tr")?).enumerate() {
+                if let Some(header) = line.select(&season_selector).next() {
+                    season = header
+                        .attr("id")
+                        .and_then(|id| id.strip_prefix("season-"))
+                        .and_then(|number| number.parse().ok())
+                        .unwrap_or_default();
+                    continue;
                 }
+
+                let Some(link) = line.select(&link_selector).next() else {
+                    continue;
+                };
+
+                let id = link
+                    .attr("href")
+                    .and_then(|href| id_regex.captures(href))
+                    .and_then(|captures| captures[1].parse().ok());
+                let id = diagnostics
+                    .check(id, "id", row, || line.html())?
+                    .unwrap_or_default();
+
+                let episode = line
+                    .select(&number_selector)
+                    .next()
+                    .and_then(|number| number.text().next())
+                    .and_then(|number| number.trim().parse().ok())
+                    .unwrap_or_default();
+
+                let name = line
+                    .select(&name_selector)
+                    .next()
+                    .unwrap_or(link)
+                    .text()
+                    .collect::<String>()
+                    .trim()
+                    .to_string();
+
+                episodes.push(model::Episode::new(
+                    &base_url, id, season, episode, name, &languages, &offset, &sort,
+                ));
             }
-            Ok(Response::Movie(movies))
         }
+        Ok(Response::Episode(episodes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Page, ParseMode, ParseWarning, Response};
+    use crate::{
+        Error, Filters, Language, OrderBy, YearFilter,
+        client::Filter,
+        core::model::{Episode, Movie, SubFormat, Subtitle},
+    };
+
+    const MOVIES: &str = include_str!("../../tests/fixtures/movies.html");
+    const SUBTITLES: &str = include_str!("../../tests/fixtures/subtitles.html");
+    const SINGLE_RESULT: &str = include_str!("../../tests/fixtures/single_result.html");
+    const EMPTY: &str = include_str!("../../tests/fixtures/empty.html");
+    const NO_RESULTS_MAINTENANCE: &str =
+        include_str!("../../tests/fixtures/no_results_maintenance.html");
+    const EPISODES: &str = include_str!("../../tests/fixtures/episodes.html");
+    const CAPTCHA: &str = include_str!("../../tests/fixtures/captcha.html");
+    const MAINTENANCE: &str = include_str!("../../tests/fixtures/maintenance.html");
+    const TOO_MANY_REQUESTS: &str = include_str!("../../tests/fixtures/too_many_requests.html");
+
+    fn create(url: &str, html: &str, filter: Option<&Filter>) -> crate::Result<Response> {
+        Response::parse(url, html, filter, ParseMode::Lenient).map(|parsed| parsed.response)
+    }
+
+    const SEARCH_URL: &str =
+        "https://www.opensubtitles.org/en/search2?MovieName=the+godfather&id=8&action=search";
+    const SUBTITLES_URL: &str =
+        "https://www.opensubtitles.org/en/search/sublanguageid-nor,swe/idmovie-8877/sort-5/asc-0";
+
+    #[test]
+    fn test_create_movies() {
+        let response = create(SEARCH_URL, MOVIES, None).unwrap();
+
+        let base = "https://www.opensubtitles.org";
+        assert_eq!(
+            response,
+            Response::Movie(vec![
+                Movie::new(base, 8877, "The Godfather (1972)".into(), "all", "", ""),
+                Movie::new(
+                    base,
+                    8878,
+                    "The Godfather: Part II (1974)".into(),
+                    "all",
+                    "",
+                    ""
+                ),
+                Movie::new(
+                    base,
+                    8879,
+                    "The Godfather: Part III (1990)".into(),
+                    "all",
+                    "",
+                    ""
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_create_movies_with_filter() {
+        let filter = Filters::default()
+            .languages(&[Language::English])
+            .page(2)
+            .order_by(OrderBy::Downloads)
+            .build();
+        let response = create(SEARCH_URL, MOVIES, Some(&filter)).unwrap();
+
+        let Response::Movie(movies) = response else {
+            panic!("expected movies");
+        };
+        assert_eq!(
+            movies[0].subtitles_link,
+            "https://www.opensubtitles.org/en/search/sublanguageid-eng/idmovie-8877/offset=40/sort-7/asc-0"
+        );
+    }
+
+    #[test]
+    fn test_create_movies_year_range() {
+        let filter = Filters::default()
+            .year(YearFilter::Between(1970, 1980))
+            .build();
+        let Response::Movie(movies) = create(SEARCH_URL, MOVIES, Some(&filter)).unwrap() else {
+            panic!("expected movies");
+        };
+
+        let years = movies.iter().map(|movie| movie.year()).collect::<Vec<_>>();
+        assert_eq!(years, vec![Some(1972), Some(1974)]);
+    }
+
+    #[test]
+    fn test_create_subtitles_skips_ad_rows() {
+        let response = create(SUBTITLES_URL, SUBTITLES, None).unwrap();
+
+        assert_eq!(
+            response,
+            Response::Subtitle(
+                Page {
+                    from: 1,
+                    to: 40,
+                    total: 53,
+                },
+                vec![
+                    Subtitle {
+                        format: Some(SubFormat::Srt),
+                        fps: Some(23.976),
+                        hearing_impaired: true,
+                        hd: true,
+                        trusted: true,
+                        ..Subtitle::new(
+                            9565071,
+                            "Pulp Fiction (1994)".into(),
+                            Some("Pulp.Fiction.1994.1080p.BluRay.x264-GROUP".into()),
+                            "Norwegian".into(),
+                            "1CD".into(),
+                            "09/08/23".into(),
+                            1234,
+                            8.0,
+                            Some("larza83".into()),
+                        )
+                        .with_uploaded_raw("09/08/2023 21:14:33".into())
+                    },
+                    Subtitle {
+                        format: Some(SubFormat::Sub),
+                        machine_translated: true,
+                        ..Subtitle::new(
+                            9100200,
+                            "Pulp Fiction (1994)".into(),
+                            None,
+                            "Swedish".into(),
+                            "2CD".into(),
+                            "01/02/21".into(),
+                            87,
+                            0.0,
+                            None,
+                        )
+                        .with_uploaded_raw("01/02/2021 08:05:00".into())
+                    },
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_create_single_result_redirect() {
+        // search2 redirects to the movie subtitles page when there is a single match
+        let url = "https://www.opensubtitles.org/en/search/sublanguageid-spa/idmovie-1400000";
+        let response = create(url, SINGLE_RESULT, None).unwrap();
+
+        let Response::Subtitle(page, subtitles) = response else {
+            panic!("expected subtitles");
+        };
+        assert_eq!(
+            page,
+            Page {
+                from: 1,
+                to: 1,
+                total: 1
+            }
+        );
+        assert_eq!(subtitles.len(), 1);
+        assert_eq!(subtitles[0].id, 9700001);
+        assert_eq!(
+            subtitles[0].name.as_deref(),
+            Some("The.Holdovers.2023.WEBRip")
+        );
+        assert_eq!(subtitles[0].downloads, 5120);
+        assert_eq!(subtitles[0].rating, 10.0);
+        assert_eq!(
+            subtitles[0].download_link,
+            "https://dl.opensubtitles.org/en/download/sub/9700001"
+        );
+    }
+
+    #[test]
+    fn test_create_empty() {
+        assert_eq!(
+            create(SEARCH_URL, EMPTY, None).unwrap(),
+            Response::Movie(vec![])
+        );
+        assert_eq!(
+            create(SUBTITLES_URL, EMPTY, None).unwrap(),
+            Response::Subtitle(Page::default(), vec![])
+        );
+        // Titles and headings repeating the search are not failure pages
+        assert_eq!(
+            create(SEARCH_URL, NO_RESULTS_MAINTENANCE, None).unwrap(),
+            Response::Movie(vec![])
+        );
+    }
+
+    #[test]
+    fn test_create_episodes() {
+        let url = "https://www.opensubtitles.org/en/search/sublanguageid-all/idmovie-12345";
+        let response = create(url, EPISODES, None).unwrap();
+
+        let base = "https://www.opensubtitles.org";
+        assert_eq!(
+            response,
+            Response::Episode(vec![
+                Episode::new(base, 749451, 1, 1, "The Target".into(), "all", "", ""),
+                Episode::new(base, 749452, 1, 2, "The Detail".into(), "all", "", ""),
+                Episode::new(base, 749470, 2, 1, "Ebb Tide".into(), "all", "", ""),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_page_from_text() {
+        assert_eq!(
+            Page::from(Some("( 41 - 80 of 205 )".to_string())),
+            Page {
+                from: 41,
+                to: 80,
+                total: 205
+            }
+        );
+        assert_eq!(Page::from(Some("no numbers".to_string())), Page::default());
+        assert_eq!(Page::from(None), Page::default());
+    }
+
+    #[test]
+    fn test_parse_clean_page_has_no_warnings() {
+        let parsed = Response::parse(SUBTITLES_URL, SUBTITLES, None, ParseMode::Strict).unwrap();
+        assert!(parsed.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_lenient_warnings() {
+        let html = SUBTITLES.replace(">1234x<", ">lots<");
+        let parsed = Response::parse(SUBTITLES_URL, &html, None, ParseMode::Lenient).unwrap();
+
+        let Response::Subtitle(_, subtitles) = parsed.response else {
+            panic!("expected subtitles");
+        };
+        assert_eq!(subtitles[0].downloads, 0);
+        assert_eq!(subtitles[1].downloads, 87);
+
+        assert_eq!(parsed.warnings.len(), 1);
+        let ParseWarning {
+            field,
+            row,
+            snippet,
+        } = &parsed.warnings[0];
+        assert_eq!((*field, *row), ("downloads", 1));
+        assert!(snippet.starts_with("<tr") && snippet.contains("name9565071"));
+        assert!(snippet.chars().count() <= 160);
+    }
+
+    #[test]
+    fn test_parse_strict_error() {
+        let html = SUBTITLES.replace(r#"title="Swedish""#, "");
+        let error = Response::parse(SUBTITLES_URL, &html, None, ParseMode::Strict).unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::Parse {
+                field: "language",
+                row: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_failure_pages() {
+        assert!(matches!(
+            create(SEARCH_URL, CAPTCHA, None),
+            Err(Error::Captcha)
+        ));
+        assert!(matches!(
+            create(SUBTITLES_URL, MAINTENANCE, None),
+            Err(Error::Maintenance)
+        ));
+        assert!(matches!(
+            create(SEARCH_URL, TOO_MANY_REQUESTS, None),
+            Err(Error::RateLimited { retry_after: None })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_response() {
+        let response = create(SUBTITLES_URL, SUBTITLES, None).unwrap();
+        let json = serde_json::to_value(&response).unwrap();
+
+        assert_eq!(json["type"], "subtitle");
+        assert_eq!(json["data"][0]["total"], 53);
+        assert_eq!(json["data"][1][0]["id"], 9565071);
+
+        let back: Response = serde_json::from_value(json).unwrap();
+        assert_eq!(back, response);
+
+        let movies = serde_json::to_value(create(SEARCH_URL, MOVIES, None).unwrap()).unwrap();
+        assert_eq!(movies["type"], "movie");
+    }
+
+    #[test]
+    fn test_uploaded_raw_fallback_to_time() {
+        let html = SUBTITLES.replace(r#"title="09/08/2023 21:14:33" "#, "");
+        let Response::Subtitle(_, subtitles) = create(SUBTITLES_URL, &html, None).unwrap() else {
+            panic!("expected subtitles");
+        };
+
+        assert_eq!(subtitles[0].uploaded, "09/08/23");
+        assert_eq!(subtitles[0].uploaded_raw, "2023-08-09T21:14:33");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_uploaded_at() {
+        let Response::Subtitle(_, subtitles) = create(SUBTITLES_URL, SUBTITLES, None).unwrap()
+        else {
+            panic!("expected subtitles");
+        };
+        assert_eq!(
+            subtitles[1].uploaded_at.map(|date| date.to_string()),
+            Some("2021-02-01 08:05:00".to_string())
+        );
+
+        let html = SUBTITLES.replace("01/02/2021 08:05:00", "someday");
+        let error = Response::parse(SUBTITLES_URL, &html, None, ParseMode::Strict).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Parse {
+                field: "uploaded_at",
+                row: 3,
+                ..
+            }
+        ));
     }
 }
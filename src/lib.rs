@@ -7,8 +7,21 @@
 //! ## Features
 //!
 //! - Search for subtitles using various criteria (language, filters, ordering, etc.).
+//! - Reusable, configurable clients ([`OpenSubs`] and `blocking::OpenSubs`) sharing connection pools.
 //! - Retrieve detailed information about movies and subtitles.
+//! - Download subtitle archives and extract their files.
+//! - Search by IMDb id or by the [`moviehash`] of a local video file.
+//! - Stream every subtitle of a search across all result pages (`OpenSubs::subtitles`).
+//! - Pluggable HTTP [`Transport`] (reqwest by default) with an in-memory [`MemoryTransport`] for tests.
+//! - Search TV series by season and episode, and walk a show through its [`Episode`] listing.
 //! - Both async and blocking APIs (enable via crate features).
+//! - `Serialize`/`Deserialize` of the models with the `serde` feature.
+//! - Typed upload timestamps (`Subtitle::uploaded_at`) with the `chrono` feature.
+//! - Detection of the character encoding of downloaded files and transcoding to UTF-8 with the
+//!   `encoding` feature.
+//! - Read, write and [`convert`](format::convert) SRT, WebVTT, SSA/ASS and MicroDVD files with [`format`].
+//! - Parse release names ([`Release`]) and [`rank`](release::rank) the subtitles of a search
+//!   against a local video file.
 //! - Strong error handling with [`Error`] and [`Result`] types.
 //!
 //!
@@ -36,6 +49,27 @@
 //! }
 //! ```
 //!
+//! ### Reusable Client
+//!
+//! ```
+//! # #[cfg(feature = "async")]
+//! use std::time::Duration;
+//! use opensubs::{OpenSubs, SearchBy};
+//!
+//! async fn some() -> opensubs::Result {
+//!     // build once, share the connection pool across searches
+//!     let client = OpenSubs::builder()
+//!         .user_agent("my-app/1.0")
+//!         .timeout(Duration::from_secs(10))
+//!         .build()?;
+//!
+//!     let results = client.search(SearchBy::Movie("holdovers")).await?;
+//!     println!("Subtitles {results:#?}");
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
 //! ### Blocking Example (feature "blocking")
 //!
 //! ```
@@ -77,8 +111,13 @@
 //!
 //! - [`client`] — Search options, filters, and search implementations.
 //! - [`core`] — Core types, response parsing, and subtitle/movie models.
-//! - [`Page`], [`Response`], [`Movie`], [`Subtitle`] — Main data structures for results.
+//! - [`moviehash`] — OpenSubtitles hash of local video files.
+//! - [`release`] — Release name parsing and ranking of subtitles against a video file.
+//! - [`format`] — Subtitle file parsing and writing ([`SubtitleDocument`], [`Cue`], [`Metadata`]).
+//! - [`Page`], [`Response`], [`Movie`], [`Episode`], [`Subtitle`], [`SubtitleFile`] — Main data structures for results.
 //! - [`Filters`], [`Language`], [`OrderBy`], [`SearchBy`] — Search configuration types.
+//! - [`OpenSubs`], [`OpenSubsBuilder`], [`Config`] — Reusable client and its configuration.
+//! - [`Transport`], [`HttpRequest`], [`HttpResponse`], [`MemoryTransport`] — HTTP transport abstraction.
 //!
 //! ## Error Handling
 //!
@@ -87,8 +126,11 @@
 //!
 //! ## Feature Flags
 //!
-//! - `async` — Enables the asynchronous API (`search`).
-//! - `blocking` — Enables the blocking (synchronous) API (`blocking::search`).
+//! - `async` — Enables the asynchronous API (`OpenSubs`, `search`).
+//! - `blocking` — Enables the blocking (synchronous) API (`blocking::OpenSubs`, `blocking::search`).
+//! - `serde` — Derives `Serialize`/`Deserialize` for the models, [`Response`] and the search types.
+//! - `chrono` — Parses the upload timestamp of subtitles into `Subtitle::uploaded_at`.
+//! - `encoding` — Enables the `encoding` module, detecting the character encoding of subtitle files.
 //!
 //! ## License
 //!
@@ -102,22 +144,34 @@
 mod client;
 mod core;
 
-pub use client::{Filters, Language, OrderBy, SearchBy};
+pub use client::{
+    Cache, CacheEntry, Config, FileCache, Filters, HttpRequest, HttpResponse, Language,
+    MemoryCache, MemoryTransport, OpenSubsBuilder, OrderBy, RateLimiter, RetryPolicy, SearchBy,
+    Sort, YearFilter,
+};
 pub use core::{
-    Page, Response,
-    model::{Movie, Subtitle},
+    Page, ParseMode, ParseWarning, Parsed, Response,
+    format::{self, Cue, Metadata, SubtitleDocument},
+    model::{Episode, Movie, SubFormat, Subtitle, SubtitleFile},
+    moviehash::{self, MovieHash},
+    release::{self, RankOptions, Ranked, Release},
 };
 
+#[cfg(feature = "encoding")]
+pub use core::encoding::{self, Confidence, Decoded};
+
 #[cfg(feature = "async")]
-pub use client::default::search;
+pub use client::default::{OpenSubs, Transport, search};
 
 #[cfg(feature = "blocking")]
 pub use client::blocking;
 
 /// Error type for all fallible operations in this crate.
 ///
-/// Wraps errors from underlying dependencies such as [`reqwest`] and [`scraper`].
+/// Wraps errors from underlying dependencies such as [`reqwest`], [`scraper`] and [`zip`].
+/// New variants may be added in minor releases, so matches need a wildcard arm.
 #[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
@@ -127,6 +181,69 @@ pub enum Error {
 
     #[error(transparent)]
     SelectorError(#[from] scraper::error::SelectorErrorKind<'static>),
+
+    #[error(transparent)]
+    ZipError(#[from] zip::result::ZipError),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    /// A field of a result row could not be parsed in [`ParseMode::Strict`].
+    #[error("cannot parse {field} at row {row}: {snippet}")]
+    Parse {
+        field: &'static str,
+        row: usize,
+        snippet: String,
+    },
+
+    /// The site answered with a captcha challenge instead of the requested page.
+    #[error("captcha challenge required by the site")]
+    Captcha,
+
+    /// The site rejected the request because too many were sent.
+    #[error("rate limited by the site (retry after {retry_after:?})")]
+    RateLimited {
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// The site is in maintenance.
+    #[error("site in maintenance")]
+    Maintenance,
+
+    /// More redirections than the configured maximum were received.
+    #[error("too many redirections (max {0})")]
+    TooManyRedirects(usize),
+
+    /// A redirection led back to an already requested URL.
+    #[error("redirection loop detected at {0}")]
+    RedirectLoop(String),
+
+    /// A redirection response had no `Location` header.
+    #[error("redirection without Location header")]
+    MissingLocation,
+
+    #[error(transparent)]
+    UrlError(#[from] url::ParseError),
+
+    /// The site answered with an unexpected HTTP status.
+    #[error("unexpected HTTP status {0}")]
+    HttpStatus(u16),
+
+    /// The subtitle format cannot be read or written by [`format`].
+    #[error("unsupported subtitle format: {}", .0.as_str())]
+    UnsupportedFormat(SubFormat),
+
+    /// The format of a subtitle file could not be detected from its content.
+    #[error("cannot detect the subtitle format")]
+    UnknownFormat,
+
+    /// The reference points of [`SubtitleDocument::resync`] are invalid.
+    #[error("invalid resync points: {0}")]
+    InvalidResync(String),
+
+    /// A frame rate given to [`SubtitleDocument::retime_fps`] is not finite or below 1.
+    #[error("invalid frame rate {0}")]
+    InvalidFps(f32),
 }
 
 /// A convenient alias for `Result` with the crate's [`Error`] type.